@@ -0,0 +1,680 @@
+// Import necessary modules and crates
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use bevy::asset::{AssetPath, LoadContext, LoadedAsset};
+use bevy::math::Affine2;
+
+use serde::Deserialize;
+
+use crate::AnimationLibrary;
+use crate::LoopMode;
+use crate::SpriteSheetError;
+use crate::animation_library::{ComposedFrame, FramePart};
+use crate::sprite_sheet::{self, SpriteSheetFrame};
+
+// Symbols can contain themselves, so the nesting is limited
+const MAX_SYMBOL_DEPTH: usize = 32;
+
+// Space between the frames drawn into the texture atlas, so they don't bleed into each other
+const FRAME_PADDING: f32 = 1f32;
+
+// The keys of Animation.json are abbreviated when the export is optimized, the aliases are
+// the keys of unoptimized exports
+#[derive(Debug, Default, Deserialize)]
+struct AnimationFile {
+    #[serde(rename = "AN", alias = "ANIMATION")]
+    animation: Animation,
+    #[serde(rename = "SD", alias = "SYMBOL_DICTIONARY", default)]
+    symbol_dictionary: SymbolDictionary,
+    #[serde(rename = "MD", alias = "metadata", default)]
+    metadata: Metadata,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Animation {
+    #[serde(rename = "N", alias = "name", default)]
+    name: String,
+    #[serde(rename = "SN", alias = "SYMBOL_name", default)]
+    symbol_name: String,
+    #[serde(rename = "TL", alias = "TIMELINE")]
+    timeline: Timeline,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct SymbolDictionary {
+    #[serde(rename = "S", alias = "Symbols", default)]
+    symbols: Vec<Symbol>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Symbol {
+    #[serde(rename = "SN", alias = "SYMBOL_name")]
+    name: String,
+    #[serde(rename = "TL", alias = "TIMELINE")]
+    timeline: Timeline,
+}
+
+#[derive(Debug, Deserialize)]
+struct Metadata {
+    #[serde(rename = "FRT", alias = "framerate", default = "default_frame_rate")]
+    frame_rate: f32,
+}
+
+impl Default for Metadata {
+    fn default() -> Self {
+        return Metadata {
+            frame_rate: default_frame_rate(),
+        };
+    }
+}
+
+fn default_frame_rate() -> f32 {
+    return 24f32;
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Timeline {
+    // The layers are listed from the top to the bottom
+    #[serde(rename = "L", alias = "LAYERS", default)]
+    layers: Vec<Layer>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Layer {
+    #[serde(rename = "FR", alias = "Frames", default)]
+    keyframes: Vec<Keyframe>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Keyframe {
+    #[serde(rename = "I", alias = "index")]
+    index: usize,
+    #[serde(rename = "DU", alias = "duration", default = "default_duration")]
+    duration: usize,
+    // The frame label starting at the keyframe
+    #[serde(rename = "N", alias = "name", default)]
+    label: Option<String>,
+    #[serde(rename = "E", alias = "elements", default)]
+    elements: Vec<Element>,
+}
+
+fn default_duration() -> usize {
+    return 1;
+}
+
+// An element is either an instance of a symbol or a part of the spritemap
+#[derive(Debug, Default, Deserialize)]
+struct Element {
+    #[serde(rename = "SI", alias = "SYMBOL_Instance", default)]
+    symbol_instance: Option<SymbolInstance>,
+    #[serde(rename = "ASI", alias = "ATLAS_SPRITE_instance", default)]
+    part_instance: Option<PartInstance>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct SymbolInstance {
+    #[serde(rename = "SN", alias = "SYMBOL_name")]
+    name: String,
+    #[serde(rename = "FF", alias = "firstFrame", default)]
+    first_frame: usize,
+    // LP (loop), PO (play once) or SF (single frame)
+    #[serde(rename = "LP", alias = "loop", default)]
+    loop_mode: String,
+    #[serde(rename = "M3D", alias = "Matrix3D", alias = "MX", default)]
+    matrix: Option<Matrix>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PartInstance {
+    #[serde(rename = "N", alias = "name")]
+    name: String,
+    #[serde(rename = "M3D", alias = "Matrix3D", alias = "MX", default)]
+    matrix: Option<Matrix>,
+}
+
+// Matrices are 4x4 column-major arrays, 2x3 arrays, or objects with the 4x4 elements
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Matrix {
+    Array(Vec<f32>),
+    Object {
+        m00: f32,
+        m01: f32,
+        m10: f32,
+        m11: f32,
+        m30: f32,
+        m31: f32,
+    },
+}
+
+impl Matrix {
+    // Converts the matrix into an affine transform, y down like in Adobe Animate
+    fn to_affine(
+        &self
+    ) -> Affine2 {
+        return match self {
+            Matrix::Array(values) if values.len() >= 16 => Affine2::from_cols_array(
+                &[values[0], values[1], values[4], values[5], values[12], values[13]]
+            ),
+            Matrix::Array(values) if values.len() >= 6 => Affine2::from_cols_array(
+                &[values[0], values[1], values[2], values[3], values[4], values[5]]
+            ),
+            Matrix::Array(_) => Affine2::IDENTITY,
+            Matrix::Object { m00, m01, m10, m11, m30, m31 } => Affine2::from_cols_array(
+                &[*m00, *m01, *m10, *m11, *m30, *m31]
+            ),
+        };
+    }
+}
+
+fn matrix_to_affine(
+    matrix: &Option<Matrix>,
+) -> Affine2 {
+    return matrix.as_ref().map_or(Affine2::IDENTITY, Matrix::to_affine);
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct SpriteMapFile {
+    #[serde(rename = "ATLAS")]
+    atlas: SpriteMapAtlas,
+    #[serde(default)]
+    meta: SpriteMapMeta,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct SpriteMapAtlas {
+    #[serde(rename = "SPRITES", default)]
+    sprites: Vec<SpriteMapEntry>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct SpriteMapEntry {
+    #[serde(rename = "SPRITE")]
+    sprite: SpriteMapSprite,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct SpriteMapSprite {
+    name: String,
+    x: f32,
+    y: f32,
+    w: f32,
+    h: f32,
+    #[serde(default)]
+    rotated: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct SpriteMapMeta {
+    image: Option<String>,
+}
+
+// A part of a spritemap, looked up by name when the frames are composed
+struct SpriteMapPart {
+    atlas: usize,
+    index: usize,
+    size: Vec2,
+    rotated: bool,
+}
+
+// Checks whether Json content is the Animation.json of an Adobe Animate texture atlas
+pub(crate) fn is_animate_atlas(
+    content: &str,
+) -> bool {
+    let Ok(serde_json::Value::Object(content)) = serde_json::from_str(content.trim_start_matches('\u{FEFF}')) else {
+        return false;
+    };
+
+    return content.contains_key("AN") || content.contains_key("ANIMATION");
+}
+
+// Deserializes the content of Animation.json
+fn parse(
+    path: &Path,
+    content: &str,
+) -> Result<AnimationFile, SpriteSheetError> {
+    // Remove the BOM if present (UTF-8 BOM is 0xEF, 0xBB, 0xBF)
+    let content = content.trim_start_matches('\u{FEFF}');
+
+    return serde_json::from_str(content).map_err(|error| SpriteSheetError::from_json(path, error));
+}
+
+// Deserializes the content of a spritemap and converts its parts into sprite sheet frames
+fn parse_spritemap(
+    path: &Path,
+    content: &str,
+) -> Result<(Option<String>, Vec<SpriteSheetFrame>), SpriteSheetError> {
+    // Remove the BOM if present (UTF-8 BOM is 0xEF, 0xBB, 0xBF)
+    let content = content.trim_start_matches('\u{FEFF}');
+
+    let spritemap: SpriteMapFile = serde_json::from_str(content).map_err(|error| SpriteSheetError::from_json(path, error))?;
+
+    let frames = spritemap.atlas.sprites.iter().map(|entry| {
+        let sprite = &entry.sprite;
+
+        return SpriteSheetFrame {
+            name: sprite.name.clone(),
+            rect: Rect::new(sprite.x, sprite.y, sprite.x + sprite.w, sprite.y + sprite.h),
+            rotated: sprite.rotated,
+            ..default()
+        };
+    }).collect();
+
+    return Ok((spritemap.meta.image, frames));
+}
+
+// Returns the path of the spritemap with the given number, they are numbered from 1
+fn spritemap_path(
+    directory: &Path,
+    number: usize,
+) -> PathBuf {
+    return directory.join(format!("spritemap{number}.json"));
+}
+
+// Composes the frames of the timelines from the parts of the spritemaps
+struct Composer<'a> {
+    symbols: HashMap<&'a str, &'a Timeline>,
+    parts: HashMap<&'a str, SpriteMapPart>,
+    missing_parts: HashSet<&'a str>,
+}
+
+impl<'a> Composer<'a> {
+    // Returns the number of frames of a timeline
+    fn timeline_length(
+        timeline: &Timeline,
+    ) -> usize {
+        return timeline.layers.iter()
+            .flat_map(|layer| layer.keyframes.iter())
+            .map(|keyframe| keyframe.index + keyframe.duration)
+            .max()
+            .unwrap_or(0);
+    }
+
+    // Adds the parts shown on a frame of a timeline, from the bottom to the top layer
+    fn compose(
+        &mut self,
+        timeline: &'a Timeline,
+        frame: usize,
+        transform: Affine2,
+        depth: usize,
+        parts: &mut Vec<FramePart>,
+    ) {
+        if depth > MAX_SYMBOL_DEPTH {
+            return;
+        }
+
+        for layer in timeline.layers.iter().rev() {
+            let keyframe = layer.keyframes.iter().find(|keyframe| {
+                keyframe.index <= frame && frame < keyframe.index + keyframe.duration
+            });
+
+            let Some(keyframe) = keyframe else {
+                continue;
+            };
+
+            for element in keyframe.elements.iter() {
+                if let Some(instance) = &element.part_instance {
+                    self.add_part(instance, transform, parts);
+                }
+
+                if let Some(instance) = &element.symbol_instance {
+                    let Some(symbol) = self.symbols.get(instance.name.as_str()).copied() else {
+                        continue;
+                    };
+
+                    let length = Composer::timeline_length(symbol);
+
+                    if length == 0 {
+                        continue;
+                    }
+
+                    // Graphic symbols play along with the timeline they are placed on
+                    let symbol_frame = instance.first_frame + (frame - keyframe.index);
+
+                    let symbol_frame = match instance.loop_mode.as_str() {
+                        "SF" | "singleframe" => instance.first_frame.min(length - 1),
+                        "PO" | "playonce" => symbol_frame.min(length - 1),
+                        _ => symbol_frame % length,
+                    };
+
+                    self.compose(
+                        symbol,
+                        symbol_frame,
+                        transform * matrix_to_affine(&instance.matrix),
+                        depth + 1,
+                        parts,
+                    );
+                }
+            }
+        }
+    }
+
+    // Adds a part of a spritemap with its transform
+    fn add_part(
+        &mut self,
+        instance: &'a PartInstance,
+        transform: Affine2,
+        parts: &mut Vec<FramePart>,
+    ) {
+        let Some(part) = self.parts.get(instance.name.as_str()) else {
+            if self.missing_parts.insert(&instance.name) {
+                println!("\x1b[38;5;196mPart ({}) doesn't exist in the spritemaps\x1b[0;0;0m", instance.name);
+            }
+
+            return;
+        };
+
+        let mut transform = transform * matrix_to_affine(&instance.matrix);
+
+        // Rotated parts are stored turned 90 degrees clockwise, so they are turned back
+        if part.rotated {
+            transform = transform * Affine2::from_cols_array(&[0f32, -1f32, 1f32, 0f32, 0f32, part.size.x]);
+        }
+
+        // Adobe Animate has y pointing down, Bevy has y pointing up
+        let flip = Affine2::from_scale(Vec2::new(1f32, -1f32));
+
+        parts.push(
+            FramePart {
+                atlas: part.atlas,
+                index: part.index,
+                size: part.size,
+                transform: flip * transform * flip,
+            }
+        );
+    }
+
+    // Composes every frame of a timeline, naming them like the frames of a Sparrow export
+    fn compose_timeline(
+        &mut self,
+        name: &str,
+        timeline: &'a Timeline,
+        frames: &mut Vec<(String, Vec<FramePart>)>,
+    ) {
+        for frame in 0..Composer::timeline_length(timeline) {
+            let mut parts = Vec::new();
+            self.compose(timeline, frame, Affine2::IDENTITY, 0, &mut parts);
+            frames.push((format!("{name}{frame:04}"), parts));
+        }
+    }
+}
+
+// Returns the area covered by the parts relative to the origin of the frame, rounded out to
+// whole pixels, y up
+pub(crate) fn part_bounds(
+    parts: &[FramePart],
+) -> Rect {
+    let corners: Vec<Vec2> = parts.iter().flat_map(|part| {
+        return [Vec2::ZERO, Vec2::new(part.size.x, 0f32), Vec2::new(0f32, -part.size.y), Vec2::new(part.size.x, -part.size.y)]
+            .map(|corner| part.transform.transform_point2(corner));
+    }).collect();
+
+    if corners.is_empty() {
+        return Rect::default();
+    }
+
+    let min = corners.iter().fold(Vec2::splat(f32::MAX), |min, corner| min.min(*corner));
+    let max = corners.iter().fold(Vec2::splat(f32::MIN), |max, corner| max.max(*corner));
+
+    return Rect::from_corners(min.floor(), max.ceil());
+}
+
+// Places the frames in rows of a texture atlas without an image, it is drawn by bake_composed_frames
+fn layout_frames(
+    frames: &[ComposedFrame],
+) -> TextureAtlas {
+    // Frames sharing their index are only stored once, the indices are given in order
+    let mut sizes: Vec<Vec2> = Vec::new();
+
+    for frame in frames.iter() {
+        if frame.atlas_index == sizes.len() {
+            sizes.push(frame.bounds.size());
+        }
+    }
+
+    let area: f32 = sizes.iter().map(|size| (size.x + FRAME_PADDING) * (size.y + FRAME_PADDING)).sum();
+
+    // Keep the texture atlas roughly square
+    let row_width = sizes.iter().map(|size| size.x).fold(area.sqrt().ceil(), f32::max);
+
+    let mut rects = Vec::new();
+    let (mut x, mut y, mut row_height) = (0f32, 0f32, 0f32);
+
+    for size in sizes.iter() {
+        if x > 0f32 && x + size.x > row_width {
+            x = 0f32;
+            y += row_height + FRAME_PADDING;
+            row_height = 0f32;
+        }
+
+        rects.push(Rect::from_corners(Vec2::new(x, y), Vec2::new(x, y) + *size));
+
+        x += size.x + FRAME_PADDING;
+        row_height = row_height.max(size.y);
+    }
+
+    let mut texture_atlas = TextureAtlas::new_empty(Handle::default(), Vec2::new(row_width, y + row_height).max(Vec2::ONE));
+
+    for rect in rects {
+        texture_atlas.add_texture(rect);
+    }
+
+    return texture_atlas;
+}
+
+// The frames of an Animation.json and the texture atlases of the parts they are composed of
+struct Composition {
+    part_atlases: Vec<Handle<TextureAtlas>>,
+    frames: Vec<ComposedFrame>,
+}
+
+// Composes the frames of an Animation.json from the texture atlases and parts of its spritemaps
+fn compose_frames(
+    animation_file: &AnimationFile,
+    spritemaps: Vec<(Handle<TextureAtlas>, Vec<SpriteSheetFrame>)>,
+) -> Composition {
+    let mut composer = Composer {
+        symbols: animation_file.symbol_dictionary.symbols.iter()
+            .map(|symbol| (symbol.name.as_str(), &symbol.timeline))
+            .collect(),
+        parts: HashMap::new(),
+        missing_parts: HashSet::new(),
+    };
+
+    let mut part_atlases = Vec::new();
+
+    for (atlas, (texture_atlas, sprites)) in spritemaps.iter().enumerate() {
+        part_atlases.push(texture_atlas.clone());
+
+        for (index, sprite) in sprites.iter().enumerate() {
+            composer.parts.insert(
+                &sprite.name,
+                SpriteMapPart {
+                    atlas,
+                    index,
+                    size: sprite.rect.size(),
+                    rotated: sprite.rotated,
+                }
+            );
+        }
+    }
+
+    let animation = &animation_file.animation;
+
+    let name = if animation.symbol_name.is_empty() { &animation.name } else { &animation.symbol_name };
+
+    // The main timeline comes first, so the frame labels are ranges of its frames
+    let mut frames = Vec::new();
+    composer.compose_timeline(name, &animation.timeline, &mut frames);
+
+    for symbol in animation_file.symbol_dictionary.symbols.iter() {
+        composer.compose_timeline(&symbol.name, &symbol.timeline, &mut frames);
+    }
+
+    let mut composed_frames: Vec<ComposedFrame> = Vec::new();
+    let mut atlas_length = 0;
+
+    for (name, parts) in frames {
+        // Frames held for several frames of the timeline are only drawn once
+        let atlas_index = match composed_frames.last() {
+            Some(previous) if previous.parts == parts => previous.atlas_index,
+            _ => {
+                atlas_length += 1;
+                atlas_length - 1
+            },
+        };
+
+        composed_frames.push(
+            ComposedFrame {
+                name,
+                bounds: part_bounds(&parts),
+                atlas_index,
+                parts,
+            }
+        );
+    }
+
+    return Composition {
+        part_atlases,
+        frames: composed_frames,
+    };
+}
+
+// Creates the AnimationLibrary of an Animation.json from its composed frames and the texture atlas they are drawn into
+fn create_library(
+    animation_file: &AnimationFile,
+    texture_atlas: Handle<TextureAtlas>,
+    composition: Composition,
+) -> AnimationLibrary {
+    let mut library = AnimationLibrary::from_composed_frames(texture_atlas, composition.part_atlases, composition.frames);
+
+    let animation = &animation_file.animation;
+
+    let fps = animation_file.metadata.frame_rate;
+
+    for layer in animation.timeline.layers.iter() {
+        for keyframe in layer.keyframes.iter() {
+            if let Some(label) = keyframe.label.as_deref().filter(|label| !label.is_empty()) {
+                let last_frame = keyframe.index + keyframe.duration.max(1) - 1;
+                library.add_animation_by_range(label, keyframe.index, last_frame, fps, LoopMode::Once, Vec2::ZERO);
+            }
+        }
+    }
+
+    return library;
+}
+
+pub fn create_animation_library(
+    path: &str,
+    texture_atlases: &mut Assets<TextureAtlas>,
+    asset_server: &AssetServer,
+) -> Result<AnimationLibrary, SpriteSheetError> {
+    let directory = Path::new(path);
+
+    let animation_path = directory.join("Animation.json");
+
+    let content = sprite_sheet::read_data_file(asset_server, &animation_path)?;
+
+    let animation_file = parse(&animation_path, &content)?;
+
+    let mut spritemaps = Vec::new();
+
+    // The first spritemap is required, the others are optional
+    for number in 1.. {
+        let data_path = spritemap_path(directory, number);
+
+        if number > 1 && !asset_server.asset_io().is_file(&data_path) {
+            break;
+        }
+
+        let content = sprite_sheet::read_data_file(asset_server, &data_path)?;
+
+        let (image_path, sprites) = parse_spritemap(&data_path, &content)?;
+
+        sprite_sheet::check_frames(&data_path, &sprites)?;
+
+        let image_path = sprite_sheet::resolve_image_path(&data_path, image_path.as_deref());
+
+        if !asset_server.asset_io().is_file(&image_path) {
+            return Err(SpriteSheetError::MissingImage { path: data_path, image_path });
+        }
+
+        let texture = asset_server.load(image_path);
+
+        let texture_atlas = texture_atlases.add(sprite_sheet::create_texture_atlas(texture, &sprites));
+
+        spritemaps.push((texture_atlas, sprites));
+    }
+
+    let composition = compose_frames(&animation_file, spritemaps);
+
+    let texture_atlas = texture_atlases.add(layout_frames(&composition.frames));
+
+    return Ok(create_library(&animation_file, texture_atlas, composition));
+}
+
+// Sets the AnimationLibrary of an Animation.json as the default asset of the load context.
+// The texture atlases of the spritemaps are added as the labeled assets spritemap1, spritemap2, ...
+// and the texture atlas the frames are drawn into as frames
+pub(crate) async fn set_animation_library_asset(
+    load_context: &mut LoadContext<'_>,
+    content: &str,
+) -> Result<(), bevy::asset::Error> {
+    let animation_path = load_context.path().to_path_buf();
+
+    let animation_file = parse(&animation_path, content)?;
+
+    let directory = animation_path.parent().unwrap_or(Path::new("")).to_path_buf();
+
+    let mut spritemaps = Vec::new();
+    let mut dependencies = Vec::new();
+
+    // The first spritemap is required, the others are optional
+    for number in 1.. {
+        let data_path = spritemap_path(&directory, number);
+
+        if number > 1 && !load_context.asset_io().is_file(&data_path) {
+            break;
+        }
+
+        let content = load_context.read_asset_bytes(&data_path).await
+            .map_err(|error| SpriteSheetError::from_asset_io(&data_path, error))?;
+
+        let content = std::str::from_utf8(&content)?;
+
+        let (image_path, sprites) = parse_spritemap(&data_path, content)?;
+
+        sprite_sheet::check_frames(&data_path, &sprites)?;
+
+        let image_path = sprite_sheet::resolve_image_path(&data_path, image_path.as_deref());
+
+        if !load_context.asset_io().is_file(&image_path) {
+            return Err(SpriteSheetError::MissingImage { path: data_path, image_path }.into());
+        }
+
+        let image_path = AssetPath::new(image_path, None);
+
+        let texture: Handle<Image> = load_context.get_handle(image_path.clone());
+
+        let texture_atlas = load_context.set_labeled_asset(
+            &format!("spritemap{number}"),
+            LoadedAsset::new(sprite_sheet::create_texture_atlas(texture, &sprites)),
+        );
+
+        spritemaps.push((texture_atlas, sprites));
+        // The spritemaps aren't dependencies, they would be loaded by the JsonLoader and fail
+        dependencies.push(image_path);
+    }
+
+    let composition = compose_frames(&animation_file, spritemaps);
+
+    let texture_atlas = load_context.set_labeled_asset("frames", LoadedAsset::new(layout_frames(&composition.frames)));
+
+    load_context.set_default_asset(
+        LoadedAsset::new(create_library(&animation_file, texture_atlas, composition)).with_dependencies(dependencies)
+    );
+
+    return Ok(());
+}
@@ -0,0 +1,184 @@
+// Import necessary modules and crates
+use std::path::Path;
+
+use bevy::prelude::*;
+use bevy::asset::{AssetLoader, AssetPath, LoadContext, LoadedAsset};
+use bevy::utils::BoxedFuture;
+
+use serde::Deserialize;
+
+use crate::AnimationLibrary;
+use crate::FrameEvent;
+use crate::LoopMode;
+use crate::SpriteSheetError;
+use crate::animation_library::AnimationFrames;
+use crate::aseprite;
+use crate::aseprite_file;
+use crate::json;
+use crate::sprite_sheet;
+
+// An animation of a definition file
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct AnimationDefinition {
+    pub(crate) name: String,
+    pub(crate) frames: AnimationFrames,
+    pub(crate) fps: f32,
+    #[serde(default)]
+    pub(crate) loop_mode: LoopMode,
+    #[serde(default)]
+    pub(crate) loop_start: usize,
+    #[serde(default)]
+    pub(crate) offset: (f32, f32),
+    #[serde(default)]
+    pub(crate) events: Vec<FrameEvent>,
+}
+
+// The content of a definition file
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct LibraryDefinition {
+    // Path of the data file of the sprite sheet, relative to the definition file
+    pub(crate) sprite_sheet: String,
+    #[serde(default)]
+    pub(crate) animations: Vec<AnimationDefinition>,
+}
+
+impl AnimationLibrary {
+    // Adds the animations of a definition file
+    pub(crate) fn add_definitions(
+        &mut self,
+        definitions: &[AnimationDefinition],
+    ) {
+        for definition in definitions.iter() {
+            let animation = self.add_animation(
+                &definition.name,
+                definition.frames.clone(),
+                definition.fps,
+                definition.loop_mode,
+                Vec2::new(definition.offset.0, definition.offset.1),
+            );
+
+            if let Some(animation) = animation {
+                animation.loop_start = definition.loop_start;
+                animation.events.extend(definition.events.iter().cloned());
+            }
+        }
+    }
+}
+
+/// Asset loader for `.anim.ron` and `.anim.json` animation definition files.
+///
+/// A definition file references the data file of a sprite sheet, relative to the definition
+/// file, and lists its animations. Loading it produces a ready to play `AnimationLibrary`,
+/// so fps, loop modes, offsets and markers can be changed without recompiling:
+///
+/// ```ron
+/// (
+///     sprite_sheet: "player.xml",
+///     animations: [
+///         (name: "idle", frames: Prefix("Idle"), fps: 24, loop_mode: Loop),
+///         (name: "walk", frames: Names(["Walk0000", "Walk0001", "Walk0002"]), fps: 12, loop_mode: Loop,
+///             events: [(frame: 1, name: "footstep")]),
+///         (name: "jump", frames: Range(10, 24), fps: 24, offset: (-5, 25)),
+///     ],
+/// )
+/// ```
+///
+/// The sprite sheet can be any file with a registered loader: `.xml`, `.json`, `.eas`, `.plist`
+/// (text or binary), `.atlas`, `.aseprite` and `.ase`. `loop_mode` defaults to `Once`,
+/// `loop_start` to `0` and `offset` to `(0, 0)`. The image and the data file of the sprite
+/// sheet are loaded as dependencies, so changes to the data file are picked up when the
+/// `AssetServer` watches for changes. The texture atlas is available as the labeled asset
+/// `atlas`, except for Aseprite files, whose libraries use the `atlas` of the file itself.
+#[derive(Debug, Default)]
+pub struct AnimationLibraryLoader;
+
+impl AssetLoader for AnimationLibraryLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let content = std::str::from_utf8(bytes)?;
+
+            // Remove the BOM if present (UTF-8 BOM is 0xEF, 0xBB, 0xBF)
+            let content = content.trim_start_matches('\u{FEFF}');
+
+            let path = load_context.path();
+
+            let definition: LibraryDefinition = if path.extension().is_some_and(|extension| extension == "ron") {
+                ron::from_str(content).map_err(|error| SpriteSheetError::from_ron(path, error))?
+            } else {
+                serde_json::from_str(content).map_err(|error| SpriteSheetError::from_json(path, error))?
+            };
+
+            // The sprite sheet is relative to the definition file
+            let data_path = path.parent().unwrap_or(Path::new("")).join(&definition.sprite_sheet);
+
+            let data = load_context.read_asset_bytes(&data_path).await
+                .map_err(|error| SpriteSheetError::from_asset_io(&data_path, error))?;
+
+            let extension = data_path.extension().and_then(|extension| extension.to_str()).unwrap_or_default();
+
+            let (mut library, image_paths) = if matches!(extension, "aseprite" | "ase") {
+                let file = aseprite_file::parse_file(&data_path, &data)?;
+
+                let name = data_path.file_stem().and_then(|name| name.to_str()).unwrap_or_default();
+                let frames = aseprite_file::create_frames(&file, name, extension);
+
+                // The image is composited by the AsepriteLoader, which loads the data file as a
+                // dependency, so the library uses its texture atlas
+                let texture_atlas = load_context.get_handle(AssetPath::new(data_path.clone(), Some("atlas".to_string())));
+
+                let mut library = AnimationLibrary::from_frames(vec![texture_atlas], &frames);
+                library.set_slices(file.slices);
+                library.add_frame_tags(&file.tags);
+
+                (library, Vec::new())
+            } else {
+                let mut pages = sprite_sheet::parse_data_file(&data_path, &data)?;
+
+                let is_json = extension == "json";
+
+                if is_json {
+                    pages.extend(json::load_multi_packs(load_context, &data_path, std::str::from_utf8(&data)?).await?);
+                }
+
+                let page_assets = sprite_sheet::set_texture_atlas_assets(
+                    load_context,
+                    &data_path,
+                    &pages,
+                )?;
+
+                let mut library = AnimationLibrary::from_frames(page_assets.texture_atlases, &sprite_sheet::join_pages(&pages));
+
+                // The frame tags and slices of Aseprite exports are kept, definitions with the name
+                // of a tag replace its animation
+                if is_json {
+                    let (tags, slices) = aseprite::parse_meta(&data_path, std::str::from_utf8(&data)?)?;
+                    library.set_slices(slices);
+                    library.add_frame_tags(&tags);
+                }
+
+                (library, page_assets.image_paths)
+            };
+
+            let data_path = AssetPath::new(data_path, None);
+
+            library.sprite_sheet = load_context.get_handle(data_path.clone());
+            library.add_definitions(&definition.animations);
+
+            load_context.set_default_asset(
+                LoadedAsset::new(library)
+                    .with_dependencies(image_paths)
+                    .with_dependency(data_path)
+            );
+
+            return Ok(());
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        return &["anim.ron", "anim.json"];
+    }
+}
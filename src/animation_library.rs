@@ -0,0 +1,1058 @@
+// Import necessary modules and crates
+use std::collections::{HashMap, HashSet};
+
+use bevy::prelude::*;
+use bevy::math::Affine2;
+use bevy::reflect::TypeUuid;
+
+use serde::Deserialize;
+
+use crate::FrameEvent;
+use crate::FrameEvents;
+use crate::Slice;
+use crate::SpriteSheet;
+use crate::SpriteSheetError;
+use crate::SpriteSheetFrame;
+use crate::animate_atlas;
+use crate::aseprite;
+use crate::sparrow;
+use crate::json;
+use crate::json_array;
+use crate::phaser;
+use crate::cocos2d;
+use crate::libgdx;
+
+/// Enum describing what happens when an animation reaches its end.
+///
+/// Passes after the first one only play the frames from `AnimationData::loop_start` on.
+/// `LoopTimes` and `PingPongPasses` count the passes, `PingPongTimes` counts the back and forth cycles.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum LoopMode {
+    /// Plays the animation once and stops on its last frame.
+    #[default]
+    Once,
+    /// Plays the animation once and hides the sprite.
+    OnceAndHide,
+    /// Loops the animation forever.
+    Loop,
+    /// Plays the animation the given number of times and stops on its last frame.
+    LoopTimes(u32),
+    /// Plays the animation forwards and backwards forever.
+    PingPong,
+    /// Plays the animation forwards and backwards the given number of times and stops on
+    /// its first frame.
+    PingPongTimes(u32),
+    /// Plays the animation forwards and backwards for the given number of passes, each
+    /// direction counting as one like the repeats of an Aseprite tag. Stops on the last frame
+    /// after an odd number of passes and on the first frame after an even number.
+    PingPongPasses(u32),
+}
+
+impl LoopMode {
+    /// Returns `true` if the animation ends after a single pass.
+    pub fn is_once(
+        &self
+    ) -> bool {
+        return matches!(self, LoopMode::Once | LoopMode::OnceAndHide);
+    }
+}
+
+// The frames an animation is made of, kept so they can be looked up again when the
+// sprite sheet is reloaded
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) enum AnimationFrames {
+    // Every frame starting with the prefix, sorted by name
+    Prefix(String),
+    // The frames with the names, in the given order
+    Names(Vec<String>),
+    // The frames from the first to the last index, both included, backwards if the first
+    // index is greater than the last one
+    Range(usize, usize),
+}
+
+impl Default for AnimationFrames {
+    fn default() -> Self {
+        return AnimationFrames::Names(Vec::new());
+    }
+}
+
+/// Struct containing animation data.
+///
+/// This struct represents the properties and settings of an animation that can be played
+/// on an `AnimatedSprite`. It includes information such as the animation's name, frames
+/// per second (fps), frame durations, loop mode and offset. The playback state lives in
+/// the `AnimatedSprite`, so the same `AnimationData` can be shared by any number of entities.
+///
+/// # Fields
+///
+/// - `name`: The name of the animation.
+/// - `fps`: The frames per second at which the animation should play.
+/// - `durations`: The duration of each frame in seconds. If it is empty, every frame
+///   lasts `1 / fps` seconds.
+/// - `loop_mode`: What happens when the animation reaches its end.
+/// - `loop_start`: The index of the frame loops restart at, so the frames before it are an
+///   intro that only plays once.
+/// - `offset`: A 2D vector representing the offset to apply to the animation.
+/// - `indices`: A vector of frame indices that make up the animation, animations without
+///   frames are stopped instead of played.
+/// - `events`: The markers that send an `AnimationEvent` when their frame is reached.
+#[derive(Debug, Default, Clone)]
+pub struct AnimationData {
+    /// The name of the animation.
+    pub name: String,
+    /// The frames per second at which the animation should play.
+    pub fps: f32,
+    /// The duration of each frame in seconds. If it is empty, every frame lasts `1 / fps` seconds.
+    pub durations: Vec<f32>,
+    /// What happens when the animation reaches its end.
+    pub loop_mode: LoopMode,
+    /// The index of the frame loops restart at, so the frames before it only play once.
+    pub loop_start: usize,
+    /// A 2D vector representing the offset to apply to the animation.
+    pub offset: Vec2,
+    /// A vector of frame indices that make up the animation, animations without frames are
+    /// stopped instead of played.
+    pub indices: Vec<usize>,
+    /// The markers that send an `AnimationEvent` when their frame is reached.
+    pub events: Vec<FrameEvent>,
+
+    pub(crate) frames: AnimationFrames,
+    // Whether the animation was added for a frame tag, so it follows the tag when the sprite sheet is reloaded
+    pub(crate) from_tag: bool,
+}
+
+impl AnimationData {
+    /// Returns the duration of a frame of the animation in seconds.
+    ///
+    /// # Parameters
+    ///
+    /// - `frame`: The index of the frame within the animation.
+    pub fn frame_duration(
+        &self,
+        frame: usize,
+    ) -> f32 {
+        return self.durations.get(frame).copied().unwrap_or(1f32 / self.fps);
+    }
+
+    /// Sets the duration of a frame of the animation.
+    ///
+    /// If the animation doesn't have per-frame durations yet, every other frame keeps
+    /// lasting `1 / fps` seconds.
+    ///
+    /// # Parameters
+    ///
+    /// - `frame`: The index of the frame within the animation.
+    /// - `duration`: The duration of the frame in seconds.
+    pub fn set_frame_duration(
+        &mut self,
+        frame: usize,
+        duration: f32,
+    ) {
+        if self.durations.len() != self.indices.len() {
+            self.durations = (0..self.indices.len()).map(|frame| self.frame_duration(frame)).collect();
+        }
+
+        if let Some(frame_duration) = self.durations.get_mut(frame) {
+            *frame_duration = duration;
+        }
+    }
+
+    /// Adds a marker to a frame of the animation.
+    ///
+    /// Whenever playback enters the frame, an `AnimationEvent` with the name and payload is sent.
+    ///
+    /// # Parameters
+    ///
+    /// - `frame`: The index of the frame within the animation.
+    /// - `name`: The name of the marker, e.g. `footstep`.
+    /// - `payload`: Additional data sent with the event.
+    ///
+    /// # Returns
+    ///
+    /// The `AnimationData`, so more markers can be added.
+    pub fn add_event(
+        &mut self,
+        frame: usize,
+        name: &str,
+        payload: Option<&str>,
+    ) -> &mut Self {
+        self.events.push(
+            FrameEvent {
+                frame,
+                name: name.to_string(),
+                payload: payload.map(str::to_string),
+            }
+        );
+
+        return self;
+    }
+
+    /// Returns the markers of a frame of the animation.
+    ///
+    /// # Parameters
+    ///
+    /// - `frame`: The index of the frame within the animation.
+    pub fn frame_events(
+        &self,
+        frame: usize,
+    ) -> impl Iterator<Item = &FrameEvent> {
+        return self.events.iter().filter(move |event| event.frame == frame);
+    }
+
+    /// Returns the frame shown at a point in time of the animation.
+    ///
+    /// Times outside of the animation are clamped to its first or last frame.
+    ///
+    /// # Parameters
+    ///
+    /// - `time`: The time since the start of the animation in seconds.
+    ///
+    /// # Returns
+    ///
+    /// The index of the frame within the animation and the time since the start of the frame.
+    pub fn frame_at_time(
+        &self,
+        time: f32,
+    ) -> (usize, f32) {
+        let last_index = self.indices.len().saturating_sub(1);
+        let mut time = time.max(0f32);
+
+        for frame in 0..last_index {
+            let frame_duration = self.frame_duration(frame);
+
+            // Frames with an invalid duration are skipped
+            if frame_duration.is_nan() || frame_duration <= 0f32 {
+                continue;
+            }
+
+            if time < frame_duration {
+                return (frame, time);
+            }
+
+            time -= frame_duration;
+        }
+
+        return (last_index, time.min(self.frame_duration(last_index)));
+    }
+
+    /// Returns the duration of the whole animation in seconds.
+    pub fn duration(
+        &self
+    ) -> f32 {
+        return (0..self.indices.len()).map(|frame| self.frame_duration(frame)).sum();
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub(crate) struct FrameInfo {
+    // Offset of the center of the frame from the center of the untrimmed frame, y up
+    pub(crate) position_offset: Vec2,
+    pub(crate) size: Vec2,
+    // Size of the untrimmed frame
+    pub(crate) source_size: Vec2,
+    // Pivot of the frame relative to the untrimmed frame, used instead of the anchor of the sprite
+    pub(crate) pivot: Option<Vec2>,
+    pub(crate) duration: Option<f32>,
+    // Index of the page the frame is stored in
+    pub(crate) page: usize,
+    // Index of the frame in the texture atlas of its page
+    pub(crate) atlas_index: usize,
+    // Whether the frame is stored rotated, until bake_rotated_frames copies it upright
+    pub(crate) rotated: bool,
+    pub(crate) counter_clockwise: bool,
+    // The parts the frame is composed of, empty for frames of a texture atlas
+    pub(crate) parts: Vec<FramePart>,
+}
+
+// A part of a composed frame, drawn from one of the part atlases of the library
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct FramePart {
+    // Index of the texture atlas in the part atlases
+    pub(crate) atlas: usize,
+    // Index of the part in the texture atlas
+    pub(crate) index: usize,
+    // Size of the part as stored in the texture atlas
+    pub(crate) size: Vec2,
+    // Transform from the top left corner of the part to the origin of the frame, y up
+    pub(crate) transform: Affine2,
+}
+
+// A frame composed of parts, baked into the texture atlas of the library by bake_composed_frames
+#[derive(Debug, Default, Clone)]
+pub(crate) struct ComposedFrame {
+    pub(crate) name: String,
+    // Area covered by the parts relative to the origin of the frame, y up
+    pub(crate) bounds: Rect,
+    // Index of the frame in the texture atlas, frames with the same parts share it
+    pub(crate) atlas_index: usize,
+    pub(crate) parts: Vec<FramePart>,
+}
+
+/// Asset containing the frames and animations of a sprite sheet.
+///
+/// An `AnimationLibrary` holds everything that doesn't change while an animation is playing:
+/// the texture atlas, the frame names, the frame offsets and the animations. It is added to
+/// `Assets<AnimationLibrary>` once and referenced by `Handle` from every `AnimatedSprite`
+/// using it, so spawning many entities from the same sprite sheet doesn't copy any of it.
+///
+/// When the `SpriteSheet` referenced by `sprite_sheet` is reloaded, e.g. because the data file
+/// was changed while the `AssetServer` watches for changes, the frames are updated and the
+/// animations look up their frames again. Libraries created by the synchronous constructors,
+/// like `from_sparrow`, read their data file once and don't reference a `SpriteSheet`, so they
+/// aren't reloaded. Load the data file as a `SpriteSheet`, or through a definition file, to
+/// reload it.
+///
+/// Frames of sprite sheets split across several images are shown using the texture atlas of
+/// their page, so the `Handle<TextureAtlas>` of an `AnimatedSprite` changes whenever its
+/// animation crosses pages.
+///
+/// # Fields
+///
+/// - `texture_atlas`: The handle to the texture atlas of the first page.
+/// - `sprite_sheet`: The handle to the sprite sheet the frames come from.
+#[derive(Debug, Default, Clone, TypeUuid)]
+#[uuid = "0b6d6c1e-3e0b-4b8e-8f3a-7a4f4b2c9d57"]
+pub struct AnimationLibrary {
+    /// The handle to the texture atlas of the first page.
+    pub texture_atlas: Handle<TextureAtlas>,
+    /// The handle to the sprite sheet the frames come from, used to update the library when
+    /// the sprite sheet is reloaded.
+    pub sprite_sheet: Handle<SpriteSheet>,
+
+    animations: Vec<AnimationData>,
+    frames: HashMap<String, usize>,
+    frame_infos: Vec<FrameInfo>,
+    pages: Vec<Handle<TextureAtlas>>,
+    part_atlases: Vec<Handle<TextureAtlas>>,
+    slices: Vec<Slice>,
+}
+
+impl AnimationLibrary {
+    /// Creates an `AnimationLibrary` from a loaded `SpriteSheet` asset.
+    ///
+    /// The `SpriteSheet` has to be loaded through the `AssetServer` first, e.g. with the
+    /// `SparrowLoader`, which can be registered with
+    /// `app.add_asset::<SpriteSheet>().init_asset_loader::<SparrowLoader>()`. To update the
+    /// library when the sprite sheet is reloaded, set `sprite_sheet` to its handle.
+    ///
+    /// # Parameters
+    ///
+    /// - `sprite_sheet`: A reference to the loaded `SpriteSheet`.
+    ///
+    /// # Returns
+    ///
+    /// The animation library using the texture atlases and the slices of the sprite sheet, with
+    /// an animation for each of its frame tags.
+    pub fn from_sprite_sheet(
+        sprite_sheet: &SpriteSheet,
+    ) -> Self {
+        let mut animation_library = AnimationLibrary::from_frames(sprite_sheet.pages.clone(), &sprite_sheet.frames);
+        animation_library.set_slices(sprite_sheet.slices.clone());
+        animation_library.add_frame_tags(&sprite_sheet.tags);
+
+        return animation_library;
+    }
+
+    /// Creates an `AnimationLibrary` from a Sparrow v1 or Sparrow v2 data format.
+    ///
+    /// # Parameters
+    ///
+    /// - `path`: The path to the sprite sheet and data file.
+    /// - `texture_atlases`: A mutable reference to the `Assets<TextureAtlas>` resource.
+    /// - `asset_server`: A reference to the `AssetServer`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the animation library if successful, or a `SpriteSheetError`
+    /// describing which file couldn't be loaded and why.
+    pub fn from_sparrow(
+        path: &str,
+        texture_atlases: &mut Assets<TextureAtlas>,
+        asset_server: &AssetServer,
+    ) -> Result<Self, SpriteSheetError> {
+        return sparrow::create_animation_library(path, texture_atlases, asset_server);
+    }
+
+    /// Creates an `AnimationLibrary` from a Starling data format.
+    ///
+    /// # Parameters
+    ///
+    /// - `path`: The path to the sprite sheet and data file.
+    /// - `texture_atlases`: A mutable reference to the `Assets<TextureAtlas>` resource.
+    /// - `asset_server`: A reference to the `AssetServer`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the animation library if successful, or a `SpriteSheetError`
+    /// describing which file couldn't be loaded and why.
+    pub fn from_starling(
+        path: &str,
+        texture_atlases: &mut Assets<TextureAtlas>,
+        asset_server: &AssetServer,
+    ) -> Result<Self, SpriteSheetError> {
+        return sparrow::create_animation_library(path, texture_atlases, asset_server);
+    }
+
+    /// Creates an `AnimationLibrary` from a JSON data format.
+    ///
+    /// The data files listed in `meta.related_multi_packs` by a TexturePacker multipack are
+    /// added as further pages.
+    ///
+    /// # Parameters
+    ///
+    /// - `path`: The path to the sprite sheet and data file.
+    /// - `texture_atlases`: A mutable reference to the `Assets<TextureAtlas>` resource.
+    /// - `asset_server`: A reference to the `AssetServer`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the animation library if successful, or a `SpriteSheetError`
+    /// describing which file couldn't be loaded and why.
+    pub fn from_json(
+        path: &str,
+        texture_atlases: &mut Assets<TextureAtlas>,
+        asset_server: &AssetServer,
+    ) -> Result<Self, SpriteSheetError> {
+        return json::create_animation_library(path, texture_atlases, asset_server);
+    }
+
+    /// Creates an `AnimationLibrary` from a JSON Array data format.
+    ///
+    /// # Parameters
+    ///
+    /// - `path`: The path to the sprite sheet and data file.
+    /// - `texture_atlases`: A mutable reference to the `Assets<TextureAtlas>` resource.
+    /// - `asset_server`: A reference to the `AssetServer`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the animation library if successful, or a `SpriteSheetError`
+    /// describing which file couldn't be loaded and why.
+    pub fn from_json_array(
+        path: &str,
+        texture_atlases: &mut Assets<TextureAtlas>,
+        asset_server: &AssetServer,
+    ) -> Result<Self, SpriteSheetError> {
+        return json_array::create_animation_library(path, false, texture_atlases, asset_server);
+    }
+
+    /// Creates an `AnimationLibrary` from an Edge Animate data format.
+    ///
+    /// # Parameters
+    ///
+    /// - `path`: The path to the sprite sheet and data file.
+    /// - `texture_atlases`: A mutable reference to the `Assets<TextureAtlas>` resource.
+    /// - `asset_server`: A reference to the `AssetServer`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the animation library if successful, or a `SpriteSheetError`
+    /// describing which file couldn't be loaded and why.
+    pub fn from_edge_animate(
+        path: &str,
+        texture_atlases: &mut Assets<TextureAtlas>,
+        asset_server: &AssetServer,
+    ) -> Result<Self, SpriteSheetError> {
+        return json_array::create_animation_library(path, true, texture_atlases, asset_server);
+    }
+
+    /// Creates an `AnimationLibrary` from an Aseprite Json export, using either the Hash or the
+    /// Array layout.
+    ///
+    /// An animation is added for each frame tag, see `add_frame_tags`, and the slices are
+    /// available through `slice`.
+    ///
+    /// # Parameters
+    ///
+    /// - `path`: The path to the sprite sheet and data file.
+    /// - `texture_atlases`: A mutable reference to the `Assets<TextureAtlas>` resource.
+    /// - `asset_server`: A reference to the `AssetServer`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the animation library if successful, or a `SpriteSheetError`
+    /// describing which file couldn't be loaded and why.
+    pub fn from_aseprite(
+        path: &str,
+        texture_atlases: &mut Assets<TextureAtlas>,
+        asset_server: &AssetServer,
+    ) -> Result<Self, SpriteSheetError> {
+        return aseprite::create_animation_library(path, texture_atlases, asset_server);
+    }
+
+    /// Creates an `AnimationLibrary` from an Adobe Animate texture atlas.
+    ///
+    /// The folder contains the `Animation.json` with the timelines and the `spritemap1.json`,
+    /// `spritemap2.json`, ... with the parts. Every frame of the main timeline and of the symbols
+    /// is composed of parts and named like the frames of a Sparrow export, e.g. `BF idle dance0000`,
+    /// so animations can be added by prefix. An animation is added for every frame label of the
+    /// main timeline. Once the images of the spritemaps are loaded, the frames are drawn into
+    /// the texture atlas of the library, so they are shown like the frames of every other format.
+    ///
+    /// # Parameters
+    ///
+    /// - `path`: The path to the folder containing the `Animation.json`.
+    /// - `texture_atlases`: A mutable reference to the `Assets<TextureAtlas>` resource.
+    /// - `asset_server`: A reference to the `AssetServer`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the animation library if successful, or a `SpriteSheetError`
+    /// describing which file couldn't be loaded and why.
+    pub fn from_animate_atlas(
+        path: &str,
+        texture_atlases: &mut Assets<TextureAtlas>,
+        asset_server: &AssetServer,
+    ) -> Result<Self, SpriteSheetError> {
+        return animate_atlas::create_animation_library(path, texture_atlases, asset_server);
+    }
+
+    /// Creates an `AnimationLibrary` from a Phaser 3 multi-atlas data format.
+    ///
+    /// Every texture is a page of the library with its own texture atlas.
+    ///
+    /// # Parameters
+    ///
+    /// - `path`: The path to the sprite sheet and data file.
+    /// - `texture_atlases`: A mutable reference to the `Assets<TextureAtlas>` resource.
+    /// - `asset_server`: A reference to the `AssetServer`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the animation library if successful, or a `SpriteSheetError`
+    /// describing which file couldn't be loaded and why.
+    pub fn from_phaser(
+        path: &str,
+        texture_atlases: &mut Assets<TextureAtlas>,
+        asset_server: &AssetServer,
+    ) -> Result<Self, SpriteSheetError> {
+        return phaser::create_animation_library(path, texture_atlases, asset_server);
+    }
+
+    /// Creates an `AnimationLibrary` from a Cocos2d-x `.plist` data format.
+    ///
+    /// # Parameters
+    ///
+    /// - `path`: The path to the sprite sheet and data file.
+    /// - `texture_atlases`: A mutable reference to the `Assets<TextureAtlas>` resource.
+    /// - `asset_server`: A reference to the `AssetServer`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the animation library if successful, or a `SpriteSheetError`
+    /// describing which file couldn't be loaded and why.
+    pub fn from_cocos2d(
+        path: &str,
+        texture_atlases: &mut Assets<TextureAtlas>,
+        asset_server: &AssetServer,
+    ) -> Result<Self, SpriteSheetError> {
+        return cocos2d::create_animation_library(path, texture_atlases, asset_server);
+    }
+
+    /// Creates an `AnimationLibrary` from a libGDX or Spine `.atlas` data format.
+    ///
+    /// Regions with an `index` are named like the frames of a Sparrow export, e.g. `run0003`.
+    /// Every page of the data file is a page of the library with its own texture atlas.
+    ///
+    /// # Parameters
+    ///
+    /// - `path`: The path to the sprite sheet and data file.
+    /// - `texture_atlases`: A mutable reference to the `Assets<TextureAtlas>` resource.
+    /// - `asset_server`: A reference to the `AssetServer`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the animation library if successful, or a `SpriteSheetError`
+    /// describing which file couldn't be loaded and why.
+    pub fn from_libgdx(
+        path: &str,
+        texture_atlases: &mut Assets<TextureAtlas>,
+        asset_server: &AssetServer,
+    ) -> Result<Self, SpriteSheetError> {
+        return libgdx::create_animation_library(path, texture_atlases, asset_server);
+    }
+
+    // Creates an AnimationLibrary without animations from the frames of a sprite sheet and the
+    // texture atlases of its pages
+    pub(crate) fn from_frames(
+        pages: Vec<Handle<TextureAtlas>>,
+        frames: &[SpriteSheetFrame],
+    ) -> Self {
+        let mut animation_library = AnimationLibrary {
+            texture_atlas: pages.first().cloned().unwrap_or_default(),
+            pages,
+            ..default()
+        };
+
+        // The frames of every page are added to its texture atlas in order
+        let mut page_sizes: Vec<usize> = Vec::new();
+
+        for (index, frame) in frames.iter().enumerate() {
+            if page_sizes.len() <= frame.page {
+                page_sizes.resize(frame.page + 1, 0);
+            }
+
+            let atlas_index = page_sizes[frame.page];
+            page_sizes[frame.page] += 1;
+
+            animation_library.frames.insert(
+                frame.name.clone(),
+                index
+            );
+
+            animation_library.frame_infos.push(
+                FrameInfo {
+                    position_offset: frame.offset,
+                    size: frame.size(),
+                    // Frames created without a source size aren't trimmed
+                    source_size: if frame.source_size == Vec2::ZERO { frame.size() } else { frame.source_size },
+                    pivot: frame.pivot,
+                    duration: frame.duration,
+                    page: frame.page,
+                    atlas_index,
+                    rotated: frame.rotated,
+                    counter_clockwise: frame.counter_clockwise,
+                    parts: Vec::new(),
+                }
+            );
+        }
+
+        return animation_library;
+    }
+
+    // Creates an AnimationLibrary without animations from frames composed of parts, e.g. the
+    // frames of an Adobe Animate texture atlas. The frames are baked into the texture atlas by
+    // bake_composed_frames once the images of the part atlases are loaded
+    pub(crate) fn from_composed_frames(
+        texture_atlas: Handle<TextureAtlas>,
+        part_atlases: Vec<Handle<TextureAtlas>>,
+        frames: Vec<ComposedFrame>,
+    ) -> Self {
+        let mut animation_library = AnimationLibrary {
+            texture_atlas: texture_atlas.clone(),
+            pages: vec![texture_atlas],
+            part_atlases,
+            ..default()
+        };
+
+        // The untrimmed frame covers every frame, so the frames are trimmed parts of it
+        let source = frames.iter()
+            .map(|frame| frame.bounds)
+            .filter(|bounds| !bounds.is_empty())
+            .reduce(|source, bounds| source.union(bounds))
+            .unwrap_or_default();
+
+        let source_size = source.size().max(Vec2::ONE);
+
+        for (index, frame) in frames.into_iter().enumerate() {
+            animation_library.frames.insert(frame.name, index);
+
+            animation_library.frame_infos.push(
+                FrameInfo {
+                    position_offset: frame.bounds.center() - source.center(),
+                    size: frame.bounds.size(),
+                    source_size,
+                    // The origin of the symbol is the pivot of every frame
+                    pivot: Some(-source.center() / source_size),
+                    atlas_index: frame.atlas_index,
+                    parts: frame.parts,
+                    ..default()
+                }
+            );
+        }
+
+        return animation_library;
+    }
+
+    /// Adds a new animation using specific frames.
+    ///
+    /// This method adds an animation to the `AnimationLibrary` using the provided frames,
+    /// frames-per-second (fps), loop mode, and offset. If the data file contains
+    /// durations for the frames, they are used instead of the fps.
+    ///
+    /// # Parameters
+    ///
+    /// - `animation_name`: Name of the animation to be added.
+    /// - `frames`: Vector of frame names that compose the animation.
+    /// - `fps`: Frames per second of the animation.
+    /// - `loop_mode`: What happens when the animation reaches its end.
+    /// - `offset`: Offset applied to the animation.
+    ///
+    /// # Returns
+    ///
+    /// The added `AnimationData`, e.g. to add markers with `add_event`, or `None` if the
+    /// animation wasn't created because none of the frames exist.
+    pub fn add_animation_by_frames(
+        &mut self,
+        animation_name: &str,
+        frames: Vec<String>,
+        fps: f32,
+        loop_mode: LoopMode,
+        offset: Vec2,
+    ) -> Option<&mut AnimationData> {
+        return self.add_animation(animation_name, AnimationFrames::Names(frames), fps, loop_mode, offset);
+    }
+
+    /// Adds a new animation using a range of frames.
+    ///
+    /// The frames are in the same order as in the data file, which is also their order in
+    /// the texture atlas. If the first frame comes after the last frame, the frames are played
+    /// backwards.
+    ///
+    /// # Parameters
+    ///
+    /// - `animation_name`: Name of the animation to be added.
+    /// - `first_frame`: Index of the first frame of the animation.
+    /// - `last_frame`: Index of the last frame of the animation, included in the animation.
+    /// - `fps`: Frames per second of the animation.
+    /// - `loop_mode`: What happens when the animation reaches its end.
+    /// - `offset`: Offset applied to the animation.
+    ///
+    /// # Returns
+    ///
+    /// The added `AnimationData`, e.g. to add markers with `add_event`, or `None` if the
+    /// animation wasn't created because none of the frames exist.
+    pub fn add_animation_by_range(
+        &mut self,
+        animation_name: &str,
+        first_frame: usize,
+        last_frame: usize,
+        fps: f32,
+        loop_mode: LoopMode,
+        offset: Vec2,
+    ) -> Option<&mut AnimationData> {
+        return self.add_animation(animation_name, AnimationFrames::Range(first_frame, last_frame), fps, loop_mode, offset);
+    }
+
+    // Adds a new animation made of the frames
+    pub(crate) fn add_animation(
+        &mut self,
+        animation_name: &str,
+        frames: AnimationFrames,
+        fps: f32,
+        loop_mode: LoopMode,
+        offset: Vec2,
+    ) -> Option<&mut AnimationData> {
+        let indices = self.frame_indices(&frames);
+
+        if indices.is_empty() {
+            println!("\x1b[38;5;196mAnimation ({animation_name}) wasn't created because it had 0 frames\x1b[0;0;0m");
+            return None;
+        }
+
+        // Check if animation already exists with this name and remove it
+        if let Some(index) = self.animation_index(animation_name) {
+            self.animations.remove(index);
+        }
+
+        let durations = self.frame_durations(&indices, fps).unwrap_or_default();
+
+        // Add the new animation
+        self.animations.push(
+            AnimationData {
+                name: animation_name.to_string(),
+                fps,
+                durations,
+                loop_mode,
+                loop_start: 0,
+                offset,
+                indices,
+                events: Vec::new(),
+                frames,
+                from_tag: false,
+            }
+        );
+
+        return self.animations.last_mut();
+    }
+
+    // Looks up the indices of the frames in the texture atlas
+    fn frame_indices(
+        &self,
+        frames: &AnimationFrames,
+    ) -> Vec<usize> {
+        return match frames {
+            AnimationFrames::Prefix(prefix) => {
+                // Collect frames with the specified prefix and sort them
+                let mut frames: Vec<&String> = self.frames.keys().filter(|frame| frame.starts_with(prefix.as_str())).collect();
+                frames.sort();
+                frames.into_iter().map(|frame| self.frames[frame]).collect()
+            },
+            AnimationFrames::Names(frames) => {
+                frames.iter().filter_map(|frame| self.frames.get(frame)).copied().collect()
+            },
+            AnimationFrames::Range(first_frame, last_frame) if first_frame > last_frame => {
+                (*last_frame..=*first_frame).rev().filter(|index| *index < self.frame_infos.len()).collect()
+            },
+            AnimationFrames::Range(first_frame, last_frame) => {
+                (*first_frame..=*last_frame).take_while(|index| *index < self.frame_infos.len()).collect()
+            },
+        };
+    }
+
+    // Returns the durations of the data file for the frames, if it has any
+    fn frame_durations(
+        &self,
+        indices: &[usize],
+        fps: f32,
+    ) -> Option<Vec<f32>> {
+        if !indices.iter().any(|index| self.frame_infos[*index].duration.is_some()) {
+            return None;
+        }
+
+        return Some(indices.iter().map(|index| self.frame_infos[*index].duration.unwrap_or(1f32 / fps)).collect());
+    }
+
+    /// Updates the frames of the library after its sprite sheet was reloaded.
+    ///
+    /// The animations look up their frames again, keeping their settings and markers.
+    /// Animations added for frame tags use the frames and loop mode of the reloaded tags, while
+    /// animations defined in code or in a definition file keep theirs, even if a tag has their
+    /// name. Animations whose frames don't exist anymore are removed. The texture atlases of the
+    /// reloaded sprite sheet are used, except for a texture atlas assigned to `texture_atlas`.
+    ///
+    /// # Parameters
+    ///
+    /// - `sprite_sheet`: A reference to the reloaded `SpriteSheet`.
+    pub(crate) fn reload_sprite_sheet(
+        &mut self,
+        sprite_sheet: &SpriteSheet,
+    ) {
+        let mut reloaded = AnimationLibrary::from_sprite_sheet(sprite_sheet);
+        reloaded.sprite_sheet = self.sprite_sheet.clone();
+
+        // The texture atlases of the reloaded sprite sheet match its frames, only a texture atlas
+        // assigned to texture_atlas is kept
+        if self.pages.first() != Some(&self.texture_atlas) {
+            reloaded.texture_atlas = self.texture_atlas.clone();
+        }
+
+        for mut animation in self.animations.drain(..) {
+            if let Some(index) = reloaded.animation_index(&animation.name) {
+                let tag_animation = reloaded.animations.remove(index);
+
+                if animation.from_tag {
+                    animation.frames = tag_animation.frames;
+                    animation.loop_mode = tag_animation.loop_mode;
+                }
+            }
+
+            animation.indices = reloaded.frame_indices(&animation.frames);
+
+            if animation.indices.is_empty() {
+                println!("\x1b[38;5;196mAnimation ({}) was removed because it had 0 frames\x1b[0;0;0m", animation.name);
+                continue;
+            }
+
+            // Durations set in code are kept, unless the data file has its own
+            if let Some(durations) = reloaded.frame_durations(&animation.indices, animation.fps) {
+                animation.durations = durations;
+            }
+
+            reloaded.animations.push(animation);
+        }
+
+        *self = reloaded;
+    }
+
+    /// Adds a new animation using frames with a specific prefix.
+    ///
+    /// This method collects frames with the specified prefix, sorts them, and then
+    /// adds the animation to the `AnimationLibrary`.
+    ///
+    /// # Parameters
+    ///
+    /// - `animation_name`: Name of the animation to be added.
+    /// - `prefix`: Prefix used to identify frames for the animation.
+    /// - `fps`: Frames per second of the animation.
+    /// - `loop_mode`: What happens when the animation reaches its end.
+    /// - `offset`: Offset applied to the animation.
+    ///
+    /// # Returns
+    ///
+    /// The added `AnimationData`, e.g. to add markers with `add_event`, or `None` if the
+    /// animation wasn't created because no frame has the prefix.
+    pub fn add_animation_by_prefix(
+        &mut self,
+        animation_name: &str,
+        prefix: &str,
+        fps: f32,
+        loop_mode: LoopMode,
+        offset: Vec2,
+    ) -> Option<&mut AnimationData> {
+        return self.add_animation(animation_name, AnimationFrames::Prefix(prefix.to_string()), fps, loop_mode, offset);
+    }
+
+    /// Adds the markers of a `FrameEvents` asset to the animations.
+    ///
+    /// Markers of animations that don't exist in the `AnimationLibrary` are ignored.
+    ///
+    /// # Parameters
+    ///
+    /// - `frame_events`: The markers loaded from a `.events.json` file.
+    pub fn add_frame_events(
+        &mut self,
+        frame_events: &FrameEvents,
+    ) {
+        for (animation_name, events) in frame_events.animations.iter() {
+            if let Some(animation) = self.animation_mut(animation_name) {
+                animation.events.extend(events.iter().cloned());
+            }
+        }
+    }
+
+    /// Retrieves an animation by its name.
+    ///
+    /// # Parameters
+    ///
+    /// - `animation_name`: Name of the animation.
+    ///
+    /// # Returns
+    ///
+    /// The `AnimationData` of the animation, or `None` if it doesn't exist.
+    pub fn animation(
+        &self,
+        animation_name: &str,
+    ) -> Option<&AnimationData> {
+        return self.animations.iter().find(|animation| animation.name == animation_name);
+    }
+
+    /// Retrieves a mutable reference to an animation by its name.
+    ///
+    /// This can be used to change the settings of an animation, e.g. its frame durations.
+    ///
+    /// # Parameters
+    ///
+    /// - `animation_name`: Name of the animation.
+    ///
+    /// # Returns
+    ///
+    /// The `AnimationData` of the animation, or `None` if it doesn't exist.
+    pub fn animation_mut(
+        &mut self,
+        animation_name: &str,
+    ) -> Option<&mut AnimationData> {
+        return self.animations.iter_mut().find(|animation| animation.name == animation_name);
+    }
+
+    /// Returns all animations of the `AnimationLibrary`.
+    pub fn animations(
+        &self
+    ) -> &[AnimationData] {
+        return &self.animations;
+    }
+
+    /// Returns the index of a frame in the texture atlas by its name.
+    pub fn frame_index(
+        &self,
+        frame_name: &str,
+    ) -> Option<usize> {
+        return self.frames.get(frame_name).copied();
+    }
+
+    // Searches for the index of the animation with the given name
+    pub(crate) fn animation_index(
+        &self,
+        animation_name: &str,
+    ) -> Option<usize> {
+        return self.animations.iter().position(|animation| animation.name == animation_name);
+    }
+
+    // Returns the animation at the given index
+    pub(crate) fn animation_at(
+        &self,
+        index: usize,
+    ) -> &AnimationData {
+        return &self.animations[index];
+    }
+
+    /// Returns the slices of the sprite sheet, e.g. the slices of an Aseprite file.
+    pub fn slices(
+        &self
+    ) -> &[Slice] {
+        return &self.slices;
+    }
+
+    /// Retrieves a slice by its name.
+    ///
+    /// Use `Slice::key_at` with the index of the frame, e.g. `TextureAtlasSprite::index`,
+    /// to get its bounds on that frame.
+    pub fn slice(
+        &self,
+        slice_name: &str,
+    ) -> Option<&Slice> {
+        return self.slices.iter().find(|slice| slice.name == slice_name);
+    }
+
+    // Replaces the slices of the library
+    pub(crate) fn set_slices(
+        &mut self,
+        slices: Vec<Slice>,
+    ) {
+        self.slices = slices;
+    }
+
+    // Returns the duration of the frame at the given texture atlas index, if the data file specifies one
+    pub(crate) fn frame_duration(
+        &self,
+        index: usize,
+    ) -> Option<f32> {
+        return self.frame_infos.get(index).and_then(|frame_info| frame_info.duration);
+    }
+
+    // Returns the information of the frame at the given texture atlas index
+    pub(crate) fn frame_info(
+        &self,
+        index: usize,
+    ) -> &FrameInfo {
+        return &self.frame_infos[index];
+    }
+
+    // Returns the texture atlas of the page the frame at the given index is stored in, the
+    // first page uses texture_atlas so it can still be replaced
+    pub(crate) fn frame_atlas(
+        &self,
+        index: usize,
+    ) -> &Handle<TextureAtlas> {
+        return match self.frame_infos[index].page {
+            0 => &self.texture_atlas,
+            page => self.pages.get(page).unwrap_or(&self.texture_atlas),
+        };
+    }
+
+    // Returns the texture atlas, the index in it and the direction of every rotated frame
+    pub(crate) fn rotated_frames(
+        &self
+    ) -> impl Iterator<Item = (&Handle<TextureAtlas>, usize, bool)> {
+        return self.frame_infos.iter().enumerate()
+            .filter(|(_, frame_info)| frame_info.rotated)
+            .map(|(index, frame_info)| (self.frame_atlas(index), frame_info.atlas_index, frame_info.counter_clockwise));
+    }
+
+    /// Returns the handles to the texture atlases of every page, starting with `texture_atlas`.
+    pub fn pages(
+        &self
+    ) -> &[Handle<TextureAtlas>] {
+        return &self.pages;
+    }
+
+    // Returns the index in the texture atlas and the parts of every frame composed of parts,
+    // frames sharing their index are only returned once
+    pub(crate) fn composed_frames(
+        &self
+    ) -> impl Iterator<Item = (usize, &[FramePart])> {
+        let mut atlas_indices = HashSet::new();
+
+        return self.frame_infos.iter()
+            .filter(|frame_info| !frame_info.parts.is_empty())
+            .filter(move |frame_info| atlas_indices.insert(frame_info.atlas_index))
+            .map(|frame_info| (frame_info.atlas_index, frame_info.parts.as_slice()));
+    }
+
+    // Returns the texture atlases the parts of the composed frames are stored in
+    pub(crate) fn part_atlases(
+        &self
+    ) -> &[Handle<TextureAtlas>] {
+        return &self.part_atlases;
+    }
+}
@@ -0,0 +1,319 @@
+// Import necessary modules and crates
+use std::path::Path;
+
+use bevy::prelude::*;
+
+use serde::Deserialize;
+
+use crate::AnimationLibrary;
+use crate::LoopMode;
+use crate::SpriteSheetError;
+use crate::animation_library::AnimationFrames;
+use crate::json;
+use crate::sprite_sheet;
+
+// Aseprite shows frames for 100 milliseconds by default
+const DEFAULT_FPS: f32 = 10f32;
+
+/// Enum describing the direction a `FrameTag` is played in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TagDirection {
+    /// Plays the frames from the first to the last one.
+    #[default]
+    Forward,
+    /// Plays the frames from the last to the first one.
+    Reverse,
+    /// Plays the frames forwards and backwards.
+    PingPong,
+    /// Plays the frames backwards and forwards.
+    PingPongReverse,
+}
+
+/// Struct representing a tag of an Aseprite file, a named range of frames.
+///
+/// An animation with the name of the tag is added to the `AnimationLibrary` of the sprite sheet.
+///
+/// # Fields
+///
+/// - `name`: The name of the tag.
+/// - `from`: The index of the first frame of the tag.
+/// - `to`: The index of the last frame of the tag, included in the tag.
+/// - `direction`: The direction the frames are played in.
+/// - `repeat`: How many times the frames are played, `None` to loop forever.
+#[derive(Debug, Default, Clone)]
+pub struct FrameTag {
+    /// The name of the tag.
+    pub name: String,
+    /// The index of the first frame of the tag.
+    pub from: usize,
+    /// The index of the last frame of the tag, included in the tag.
+    pub to: usize,
+    /// The direction the frames are played in.
+    pub direction: TagDirection,
+    /// How many times the frames are played, `None` to loop forever.
+    pub repeat: Option<u32>,
+}
+
+impl FrameTag {
+    // Returns the frames and the loop mode of the animation playing the tag
+    fn animation(
+        &self
+    ) -> (AnimationFrames, LoopMode) {
+        let forward = AnimationFrames::Range(self.from, self.to);
+        let backward = AnimationFrames::Range(self.to, self.from);
+
+        // Aseprite counts every direction of a ping-pong as a repeat
+        let passes = self.repeat.map(|repeat| repeat.max(1));
+
+        return match self.direction {
+            TagDirection::Forward => (forward, self.repeat.map_or(LoopMode::Loop, LoopMode::LoopTimes)),
+            TagDirection::Reverse => (backward, self.repeat.map_or(LoopMode::Loop, LoopMode::LoopTimes)),
+            TagDirection::PingPong => (forward, passes.map_or(LoopMode::PingPong, LoopMode::PingPongPasses)),
+            TagDirection::PingPongReverse => (backward, passes.map_or(LoopMode::PingPong, LoopMode::PingPongPasses)),
+        };
+    }
+}
+
+/// Struct representing the bounds of a `Slice` from a frame on.
+///
+/// All rectangles are in pixels, relative to the top left corner of the untrimmed frame, with
+/// y pointing down.
+///
+/// # Fields
+///
+/// - `frame`: The index of the frame the key starts at. It applies until the next key.
+/// - `bounds`: The bounds of the slice.
+/// - `center`: The center of a 9-slice, relative to `bounds`.
+/// - `pivot`: The pivot of the slice, relative to `bounds`.
+#[derive(Debug, Default, Clone)]
+pub struct SliceKey {
+    /// The index of the frame the key starts at. It applies until the next key.
+    pub frame: usize,
+    /// The bounds of the slice.
+    pub bounds: Rect,
+    /// The center of a 9-slice, relative to `bounds`.
+    pub center: Option<Rect>,
+    /// The pivot of the slice, relative to `bounds`.
+    pub pivot: Option<Vec2>,
+}
+
+/// Struct representing a slice of an Aseprite file, a named rectangle that can change from
+/// frame to frame, e.g. a hitbox.
+///
+/// # Fields
+///
+/// - `name`: The name of the slice.
+/// - `data`: The user data of the slice.
+/// - `keys`: The bounds of the slice, sorted by frame.
+#[derive(Debug, Default, Clone)]
+pub struct Slice {
+    /// The name of the slice.
+    pub name: String,
+    /// The user data of the slice.
+    pub data: Option<String>,
+    /// The bounds of the slice, sorted by frame.
+    pub keys: Vec<SliceKey>,
+}
+
+impl Slice {
+    /// Returns the key of the slice on a frame.
+    ///
+    /// # Parameters
+    ///
+    /// - `frame`: The index of the frame in the texture atlas.
+    ///
+    /// # Returns
+    ///
+    /// The last key starting at or before the frame, or `None` if the slice doesn't exist yet.
+    pub fn key_at(
+        &self,
+        frame: usize,
+    ) -> Option<&SliceKey> {
+        return self.keys.iter().rev().find(|key| key.frame <= frame);
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Bounds {
+    x: f32,
+    y: f32,
+    w: f32,
+    h: f32,
+}
+
+impl Bounds {
+    fn to_rect(
+        &self
+    ) -> Rect {
+        return Rect::new(self.x, self.y, self.x + self.w, self.y + self.h);
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Pivot {
+    x: f32,
+    y: f32,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct TagData {
+    name: String,
+    from: usize,
+    to: usize,
+    #[serde(default)]
+    direction: String,
+    // A string in the files of Aseprite, a number in some other tools
+    #[serde(default)]
+    repeat: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct SliceKeyData {
+    frame: usize,
+    bounds: Bounds,
+    #[serde(default)]
+    center: Option<Bounds>,
+    #[serde(default)]
+    pivot: Option<Pivot>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct SliceData {
+    name: String,
+    #[serde(default)]
+    data: Option<String>,
+    #[serde(default)]
+    keys: Vec<SliceKeyData>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Meta {
+    #[serde(rename = "frameTags", default)]
+    frame_tags: Vec<TagData>,
+    #[serde(default)]
+    slices: Vec<SliceData>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct MetaContent {
+    #[serde(default)]
+    meta: Meta,
+}
+
+// Converts the direction of a tag as written in the data file
+pub(crate) fn parse_direction(
+    direction: &str,
+) -> TagDirection {
+    return match direction {
+        "reverse" => TagDirection::Reverse,
+        "pingpong" => TagDirection::PingPong,
+        "pingpong_reverse" => TagDirection::PingPongReverse,
+        _ => TagDirection::Forward,
+    };
+}
+
+// Deserializes the frame tags and slices of Json content, they are empty if it wasn't exported by Aseprite
+pub(crate) fn parse_meta(
+    path: &Path,
+    content: &str,
+) -> Result<(Vec<FrameTag>, Vec<Slice>), SpriteSheetError> {
+    // Remove the BOM if present (UTF-8 BOM is 0xEF, 0xBB, 0xBF)
+    let content = content.trim_start_matches('\u{FEFF}');
+
+    let json_data: MetaContent = serde_json::from_str(content).map_err(|error| SpriteSheetError::from_json(path, error))?;
+
+    let tags = json_data.meta.frame_tags.iter().map(|tag| {
+        let repeat = tag.repeat.as_ref().and_then(|repeat| match repeat {
+            serde_json::Value::String(repeat) => repeat.parse().ok(),
+            repeat => repeat.as_u64().map(|repeat| repeat as u32),
+        });
+
+        return FrameTag {
+            name: tag.name.clone(),
+            from: tag.from,
+            to: tag.to,
+            direction: parse_direction(&tag.direction),
+            // A repeat of 0 means forever
+            repeat: repeat.filter(|repeat| *repeat > 0),
+        };
+    }).collect();
+
+    let slices = json_data.meta.slices.iter().map(|slice| {
+        let mut keys: Vec<SliceKey> = slice.keys.iter().map(|key| {
+            return SliceKey {
+                frame: key.frame,
+                bounds: key.bounds.to_rect(),
+                center: key.center.as_ref().map(Bounds::to_rect),
+                pivot: key.pivot.as_ref().map(|pivot| Vec2::new(pivot.x, pivot.y)),
+            };
+        }).collect();
+
+        keys.sort_by_key(|key| key.frame);
+
+        return Slice {
+            name: slice.name.clone(),
+            data: slice.data.clone(),
+            keys,
+        };
+    }).collect();
+
+    return Ok((tags, slices));
+}
+
+impl AnimationLibrary {
+    /// Adds an animation for every frame tag.
+    ///
+    /// The animations have the names of the tags and play their frames in the direction of the
+    /// tag, using the durations of the frames. Tags that repeat forever loop, the others play
+    /// the number of times they repeat.
+    ///
+    /// # Parameters
+    ///
+    /// - `tags`: The frame tags of the sprite sheet.
+    pub fn add_frame_tags(
+        &mut self,
+        tags: &[FrameTag],
+    ) {
+        for tag in tags.iter() {
+            // The fps is only used for frames without a duration
+            let fps = self.frame_duration(tag.from)
+                .filter(|duration| *duration > 0f32)
+                .map_or(DEFAULT_FPS, |duration| 1f32 / duration);
+
+            let (frames, loop_mode) = tag.animation();
+
+            if let Some(animation) = self.add_animation(&tag.name, frames, fps, loop_mode, Vec2::ZERO) {
+                animation.from_tag = true;
+            }
+        }
+    }
+}
+
+pub fn create_animation_library(
+    path: &str,
+    texture_atlases: &mut Assets<TextureAtlas>,
+    asset_server: &AssetServer,
+) -> Result<AnimationLibrary, SpriteSheetError> {
+    let data_path = format!("{path}.json");
+
+    let data_path = Path::new(&data_path);
+
+    // Load Json content from file
+    let content = sprite_sheet::read_data_file(asset_server, data_path)?;
+
+    let pages = json::parse_layout(data_path, &content)?;
+
+    let (tags, slices) = parse_meta(data_path, &content)?;
+
+    let mut animation_library = sprite_sheet::create_animation_library(
+        data_path,
+        pages,
+        texture_atlases,
+        asset_server,
+    )?;
+
+    animation_library.set_slices(slices);
+    animation_library.add_frame_tags(&tags);
+
+    return Ok(animation_library);
+}
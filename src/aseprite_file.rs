@@ -0,0 +1,792 @@
+// Import necessary modules and crates
+use std::io::Read;
+use std::path::Path;
+
+use bevy::prelude::*;
+use bevy::asset::{AssetLoader, LoadContext, LoadedAsset};
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::utils::BoxedFuture;
+
+use flate2::read::ZlibDecoder;
+
+use crate::FrameTag;
+use crate::Slice;
+use crate::SliceKey;
+use crate::SpriteSheet;
+use crate::SpriteSheetError;
+use crate::SpriteSheetFrame;
+use crate::TagDirection;
+use crate::sprite_sheet;
+
+const FILE_MAGIC: u16 = 0xA5E0;
+const FRAME_MAGIC: u16 = 0xF1FA;
+
+const OLD_PALETTE_CHUNK: u16 = 0x0004;
+const OLD_PALETTE_64_CHUNK: u16 = 0x0011;
+const LAYER_CHUNK: u16 = 0x2004;
+const CEL_CHUNK: u16 = 0x2005;
+const TAGS_CHUNK: u16 = 0x2018;
+const PALETTE_CHUNK: u16 = 0x2019;
+const USER_DATA_CHUNK: u16 = 0x2020;
+const SLICE_CHUNK: u16 = 0x2022;
+
+const LAYER_VISIBLE: u16 = 1;
+const LAYER_BACKGROUND: u16 = 8;
+const LAYER_REFERENCE: u16 = 64;
+
+const LAYER_IMAGE: u16 = 0;
+
+// Pixels of indexed images are a single byte, so palettes can't have more colors
+const MAX_PALETTE_SIZE: usize = 256;
+
+// The opacity of the layers is only valid if this flag of the header is set
+const HEADER_LAYER_OPACITY: u32 = 1;
+
+// Reads the little-endian values of an Aseprite file
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(
+        bytes: &'a [u8],
+    ) -> Self {
+        return Reader { bytes, position: 0 };
+    }
+
+    fn take(
+        &mut self,
+        length: usize,
+    ) -> Result<&'a [u8], String> {
+        let end = self.position.checked_add(length).filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| format!("unexpected end of data at byte {}", self.position))?;
+
+        let bytes = &self.bytes[self.position..end];
+        self.position = end;
+
+        return Ok(bytes);
+    }
+
+    fn skip(
+        &mut self,
+        length: usize,
+    ) -> Result<(), String> {
+        return self.take(length).map(|_| ());
+    }
+
+    fn rest(
+        &mut self
+    ) -> &'a [u8] {
+        let bytes = &self.bytes[self.position.min(self.bytes.len())..];
+        self.position = self.bytes.len();
+
+        return bytes;
+    }
+
+    fn byte(
+        &mut self
+    ) -> Result<u8, String> {
+        return Ok(self.take(1)?[0]);
+    }
+
+    fn word(
+        &mut self
+    ) -> Result<u16, String> {
+        let bytes = self.take(2)?;
+        return Ok(u16::from_le_bytes([bytes[0], bytes[1]]));
+    }
+
+    fn short(
+        &mut self
+    ) -> Result<i16, String> {
+        return Ok(self.word()? as i16);
+    }
+
+    fn dword(
+        &mut self
+    ) -> Result<u32, String> {
+        let bytes = self.take(4)?;
+        return Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]));
+    }
+
+    fn long(
+        &mut self
+    ) -> Result<i32, String> {
+        return Ok(self.dword()? as i32);
+    }
+
+    fn string(
+        &mut self
+    ) -> Result<String, String> {
+        let length = self.word()? as usize;
+        return Ok(String::from_utf8_lossy(self.take(length)?).into_owned());
+    }
+}
+
+struct Header {
+    frames: u16,
+    width: u16,
+    height: u16,
+    color_depth: u16,
+    flags: u32,
+    transparent_index: u8,
+}
+
+struct Layer {
+    flags: u16,
+    layer_type: u16,
+    child_level: u16,
+    opacity: u8,
+}
+
+enum CelContent {
+    // The pixels in the color depth of the file
+    Image {
+        width: u16,
+        height: u16,
+        pixels: Vec<u8>,
+    },
+    // The cel of the same layer on the frame is used
+    Linked(u16),
+    // Tilemaps aren't supported
+    Unsupported,
+}
+
+struct Cel {
+    layer: u16,
+    x: i16,
+    y: i16,
+    opacity: u8,
+    z_index: i16,
+    content: CelContent,
+}
+
+struct Frame {
+    duration: u16,
+    cels: Vec<Cel>,
+}
+
+// The content of an Aseprite file
+pub(crate) struct AsepriteFile {
+    header: Header,
+    palette: Vec<[u8; 4]>,
+    layers: Vec<Layer>,
+    frames: Vec<Frame>,
+    pub(crate) tags: Vec<FrameTag>,
+    pub(crate) slices: Vec<Slice>,
+}
+
+// Parses the header, the frames and their chunks
+fn parse(
+    bytes: &[u8],
+) -> Result<AsepriteFile, String> {
+    let mut reader = Reader::new(bytes);
+
+    reader.skip(4)?; // file size
+
+    if reader.word()? != FILE_MAGIC {
+        return Err("not an Aseprite file".to_string());
+    }
+
+    let frames = reader.word()?;
+    let width = reader.word()?;
+    let height = reader.word()?;
+    let color_depth = reader.word()?;
+    let flags = reader.dword()?;
+    reader.skip(2 + 4 + 4)?; // speed, reserved
+    let transparent_index = reader.byte()?;
+    reader.skip(3 + 2 + 1 + 1 + 2 + 2 + 2 + 2 + 84)?; // number of colors, pixel ratio, grid, reserved
+
+    if !matches!(color_depth, 8 | 16 | 32) {
+        return Err(format!("unsupported color depth {color_depth}"));
+    }
+
+    let mut file = AsepriteFile {
+        header: Header { frames, width, height, color_depth, flags, transparent_index },
+        palette: Vec::new(),
+        layers: Vec::new(),
+        frames: Vec::new(),
+        tags: Vec::new(),
+        slices: Vec::new(),
+    };
+
+    let mut has_new_palette = false;
+
+    for _ in 0..frames {
+        let frame_start = reader.position;
+        let frame_size = reader.dword()? as usize;
+
+        if reader.word()? != FRAME_MAGIC {
+            return Err(format!("invalid frame at byte {frame_start}"));
+        }
+
+        let old_chunk_count = reader.word()?;
+        let duration = reader.word()?;
+        reader.skip(2)?;
+        let new_chunk_count = reader.dword()?;
+
+        let chunk_count = if new_chunk_count == 0 { old_chunk_count as u32 } else { new_chunk_count };
+
+        let mut frame = Frame { duration, cels: Vec::new() };
+        let mut last_chunk_type = 0;
+
+        for _ in 0..chunk_count {
+            let chunk_size = reader.dword()? as usize;
+            let chunk_type = reader.word()?;
+            let mut chunk = Reader::new(reader.take(chunk_size.saturating_sub(6))?);
+
+            match chunk_type {
+                OLD_PALETTE_CHUNK | OLD_PALETTE_64_CHUNK if !has_new_palette => {
+                    parse_old_palette(&mut chunk, chunk_type == OLD_PALETTE_64_CHUNK, &mut file.palette)?;
+                },
+                PALETTE_CHUNK => {
+                    has_new_palette = true;
+                    parse_palette(&mut chunk, &mut file.palette)?;
+                },
+                LAYER_CHUNK => file.layers.push(parse_layer(&mut chunk)?),
+                CEL_CHUNK => frame.cels.push(parse_cel(&mut chunk, color_depth)?),
+                TAGS_CHUNK => file.tags.extend(parse_tags(&mut chunk)?),
+                SLICE_CHUNK => file.slices.push(parse_slice(&mut chunk)?),
+                // The user data of a slice follows it
+                USER_DATA_CHUNK if last_chunk_type == SLICE_CHUNK => {
+                    let text = parse_user_data_text(&mut chunk)?;
+
+                    if let Some(slice) = file.slices.last_mut() {
+                        slice.data = text;
+                    }
+                },
+                _ => {},
+            }
+
+            last_chunk_type = chunk_type;
+        }
+
+        file.frames.push(frame);
+
+        // Continue after the frame, even if some of its chunks weren't read completely
+        reader.position = frame_start + frame_size.max(16);
+    }
+
+    return Ok(file);
+}
+
+fn parse_old_palette(
+    chunk: &mut Reader,
+    is_64_colors: bool,
+    palette: &mut Vec<[u8; 4]>,
+) -> Result<(), String> {
+    let packet_count = chunk.word()?;
+    let mut index = 0;
+
+    for _ in 0..packet_count {
+        index += chunk.byte()? as usize;
+
+        let color_count = match chunk.byte()? {
+            0 => 256,
+            color_count => color_count as usize,
+        };
+
+        if index + color_count > MAX_PALETTE_SIZE {
+            return Err(format!("palette has more than {MAX_PALETTE_SIZE} colors"));
+        }
+
+        for _ in 0..color_count {
+            let mut color = [chunk.byte()?, chunk.byte()?, chunk.byte()?, 255];
+
+            // Colors of the old 64 color palettes range from 0 to 63
+            if is_64_colors {
+                for channel in color.iter_mut().take(3) {
+                    *channel = (*channel as u32 * 255 / 63) as u8;
+                }
+            }
+
+            if palette.len() <= index {
+                palette.resize(index + 1, [0, 0, 0, 0]);
+            }
+
+            palette[index] = color;
+            index += 1;
+        }
+    }
+
+    return Ok(());
+}
+
+fn parse_palette(
+    chunk: &mut Reader,
+    palette: &mut Vec<[u8; 4]>,
+) -> Result<(), String> {
+    let size = chunk.dword()? as usize;
+    let first = chunk.dword()? as usize;
+    let last = chunk.dword()? as usize;
+    chunk.skip(8)?;
+
+    if first > last || last >= MAX_PALETTE_SIZE || size > MAX_PALETTE_SIZE {
+        return Err(format!("invalid palette with {size} colors from {first} to {last}"));
+    }
+
+    if palette.len() <= last {
+        palette.resize(size.max(last + 1), [0, 0, 0, 0]);
+    }
+
+    for color in palette[first..=last].iter_mut() {
+        let flags = chunk.word()?;
+        *color = [chunk.byte()?, chunk.byte()?, chunk.byte()?, chunk.byte()?];
+
+        // The name of the color
+        if flags & 1 != 0 {
+            chunk.string()?;
+        }
+    }
+
+    return Ok(());
+}
+
+fn parse_layer(
+    chunk: &mut Reader,
+) -> Result<Layer, String> {
+    let flags = chunk.word()?;
+    let layer_type = chunk.word()?;
+    let child_level = chunk.word()?;
+    chunk.skip(2 + 2 + 2)?; // default width and height, blend mode
+    let opacity = chunk.byte()?;
+
+    return Ok(Layer { flags, layer_type, child_level, opacity });
+}
+
+fn parse_cel(
+    chunk: &mut Reader,
+    color_depth: u16,
+) -> Result<Cel, String> {
+    let layer = chunk.word()?;
+    let x = chunk.short()?;
+    let y = chunk.short()?;
+    let opacity = chunk.byte()?;
+    let cel_type = chunk.word()?;
+    let z_index = chunk.short()?;
+    chunk.skip(5)?;
+
+    let bytes_per_pixel = color_depth as usize / 8;
+
+    let content = match cel_type {
+        0 => {
+            let width = chunk.word()?;
+            let height = chunk.word()?;
+            let pixels = chunk.take(width as usize * height as usize * bytes_per_pixel)?.to_vec();
+
+            CelContent::Image { width, height, pixels }
+        },
+        1 => CelContent::Linked(chunk.word()?),
+        2 => {
+            let width = chunk.word()?;
+            let height = chunk.word()?;
+
+            let size = width as usize * height as usize * bytes_per_pixel;
+
+            // Only the pixels of the cel are decompressed, even if the data is larger
+            let mut pixels = Vec::new();
+            ZlibDecoder::new(chunk.rest()).take(size as u64).read_to_end(&mut pixels)
+                .map_err(|error| format!("invalid compressed cel: {error}"))?;
+
+            if pixels.len() < size {
+                return Err("compressed cel is smaller than its size".to_string());
+            }
+
+            CelContent::Image { width, height, pixels }
+        },
+        _ => CelContent::Unsupported,
+    };
+
+    return Ok(Cel { layer, x, y, opacity, z_index, content });
+}
+
+fn parse_tags(
+    chunk: &mut Reader,
+) -> Result<Vec<FrameTag>, String> {
+    let tag_count = chunk.word()?;
+    chunk.skip(8)?;
+
+    let mut tags = Vec::new();
+
+    for _ in 0..tag_count {
+        let from = chunk.word()? as usize;
+        let to = chunk.word()? as usize;
+
+        let direction = match chunk.byte()? {
+            1 => TagDirection::Reverse,
+            2 => TagDirection::PingPong,
+            3 => TagDirection::PingPongReverse,
+            _ => TagDirection::Forward,
+        };
+
+        let repeat = chunk.word()? as u32;
+        chunk.skip(6 + 3 + 1)?; // reserved, color
+        let name = chunk.string()?;
+
+        tags.push(
+            FrameTag {
+                name,
+                from,
+                to,
+                direction,
+                // A repeat of 0 means forever
+                repeat: Some(repeat).filter(|repeat| *repeat > 0),
+            }
+        );
+    }
+
+    return Ok(tags);
+}
+
+fn parse_slice(
+    chunk: &mut Reader,
+) -> Result<Slice, String> {
+    let key_count = chunk.dword()?;
+    let flags = chunk.dword()?;
+    chunk.skip(4)?;
+    let name = chunk.string()?;
+
+    let mut keys = Vec::new();
+
+    for _ in 0..key_count {
+        let frame = chunk.dword()? as usize;
+        let bounds = read_rect(chunk)?;
+
+        // 9-slice
+        let center = if flags & 1 != 0 { Some(read_rect(chunk)?) } else { None };
+
+        let pivot = if flags & 2 != 0 {
+            Some(Vec2::new(chunk.long()? as f32, chunk.long()? as f32))
+        } else {
+            None
+        };
+
+        keys.push(SliceKey { frame, bounds, center, pivot });
+    }
+
+    keys.sort_by_key(|key| key.frame);
+
+    return Ok(Slice { name, data: None, keys });
+}
+
+fn parse_user_data_text(
+    chunk: &mut Reader,
+) -> Result<Option<String>, String> {
+    let flags = chunk.dword()?;
+
+    if flags & 1 == 0 {
+        return Ok(None);
+    }
+
+    return Ok(Some(chunk.string()?));
+}
+
+fn read_rect(
+    chunk: &mut Reader,
+) -> Result<Rect, String> {
+    let x = chunk.long()? as f32;
+    let y = chunk.long()? as f32;
+    let width = chunk.dword()? as f32;
+    let height = chunk.dword()? as f32;
+
+    return Ok(Rect::new(x, y, x + width, y + height));
+}
+
+impl AsepriteFile {
+    // Returns which layers are shown, a layer is hidden if it or one of its groups is hidden
+    fn visible_layers(
+        &self
+    ) -> Vec<bool> {
+        let mut groups: Vec<bool> = Vec::new();
+
+        return self.layers.iter().map(|layer| {
+            groups.truncate(layer.child_level as usize);
+
+            let is_visible = layer.flags & LAYER_VISIBLE != 0
+                && layer.flags & LAYER_REFERENCE == 0
+                && groups.iter().all(|group| *group);
+
+            groups.push(is_visible);
+
+            return is_visible;
+        }).collect();
+    }
+
+    // Converts a pixel in the color depth of the file into RGBA
+    fn pixel(
+        &self,
+        bytes: &[u8],
+        is_background: bool,
+    ) -> [u8; 4] {
+        return match self.header.color_depth {
+            32 => [bytes[0], bytes[1], bytes[2], bytes[3]],
+            16 => [bytes[0], bytes[0], bytes[0], bytes[1]],
+            _ if bytes[0] == self.header.transparent_index && !is_background => [0, 0, 0, 0],
+            _ => self.palette.get(bytes[0] as usize).copied().unwrap_or([0, 0, 0, 0]),
+        };
+    }
+
+    // Returns the cel of a layer on a frame, following linked cels
+    //
+    // Returns None for links that never reach a cel, e.g. two frames linking to each other
+    fn cel(
+        &self,
+        frame: usize,
+        layer: u16,
+    ) -> Option<&Cel> {
+        let mut frame = frame;
+
+        // Every link leads to another frame, so following more links than there are frames is a cycle
+        for _ in 0..=self.frames.len() {
+            let cel = self.frames.get(frame)?.cels.iter().find(|cel| cel.layer == layer)?;
+
+            match cel.content {
+                CelContent::Linked(linked_frame) if linked_frame as usize != frame => frame = linked_frame as usize,
+                _ => return Some(cel),
+            }
+        }
+
+        return None;
+    }
+
+    // Draws the visible layers of a frame into a region of the atlas, using the normal blend mode
+    fn composite(
+        &self,
+        frame: usize,
+        visible_layers: &[bool],
+        atlas: &mut [u8],
+        atlas_width: usize,
+        origin: (usize, usize),
+    ) {
+        let width = self.header.width as i32;
+        let height = self.header.height as i32;
+        let bytes_per_pixel = self.header.color_depth as usize / 8;
+
+        // The z-index moves a cel in front of or behind the cels of other layers
+        let mut cels: Vec<&Cel> = self.frames[frame].cels.iter().collect();
+        cels.sort_by_key(|cel| (cel.layer as i32 + cel.z_index as i32, cel.z_index));
+
+        for order_cel in cels {
+            let layer_index = order_cel.layer as usize;
+
+            let Some(layer) = self.layers.get(layer_index) else {
+                continue;
+            };
+
+            if !visible_layers[layer_index] || layer.layer_type != LAYER_IMAGE {
+                continue;
+            }
+
+            // Linked cels have their own position and opacity, but share the pixels
+            let Some(Cel { content: CelContent::Image { width: cel_width, height: cel_height, pixels }, .. }) = self.cel(frame, order_cel.layer) else {
+                continue;
+            };
+
+            let layer_opacity = if self.header.flags & HEADER_LAYER_OPACITY != 0 { layer.opacity } else { 255 };
+            let opacity = order_cel.opacity as f32 / 255f32 * layer_opacity as f32 / 255f32;
+            let is_background = layer.flags & LAYER_BACKGROUND != 0;
+
+            for cel_y in 0..*cel_height as i32 {
+                let y = order_cel.y as i32 + cel_y;
+
+                if y < 0 || y >= height {
+                    continue;
+                }
+
+                for cel_x in 0..*cel_width as i32 {
+                    let x = order_cel.x as i32 + cel_x;
+
+                    if x < 0 || x >= width {
+                        continue;
+                    }
+
+                    let source_index = (cel_y as usize * *cel_width as usize + cel_x as usize) * bytes_per_pixel;
+                    let source = self.pixel(&pixels[source_index..source_index + bytes_per_pixel], is_background);
+
+                    let target_index = ((origin.1 + y as usize) * atlas_width + origin.0 + x as usize) * 4;
+                    let target = &mut atlas[target_index..target_index + 4];
+
+                    blend(source, opacity, target);
+                }
+            }
+        }
+    }
+}
+
+// Blends a pixel over another one using straight alpha
+fn blend(
+    source: [u8; 4],
+    opacity: f32,
+    target: &mut [u8],
+) {
+    let source_alpha = source[3] as f32 / 255f32 * opacity;
+
+    if source_alpha <= 0f32 {
+        return;
+    }
+
+    let target_alpha = target[3] as f32 / 255f32;
+    let alpha = source_alpha + target_alpha * (1f32 - source_alpha);
+
+    for channel in 0..3 {
+        let color = (source[channel] as f32 * source_alpha + target[channel] as f32 * target_alpha * (1f32 - source_alpha)) / alpha;
+        target[channel] = color.round() as u8;
+    }
+
+    target[3] = (alpha * 255f32).round() as u8;
+}
+
+// Returns the number of columns of the grid the frames are laid out in
+fn grid_columns(
+    file: &AsepriteFile,
+) -> usize {
+    return (file.frames.len().max(1) as f32).sqrt().ceil() as usize;
+}
+
+// Lays out every frame in a grid and returns the frames, without compositing them
+pub(crate) fn create_frames(
+    file: &AsepriteFile,
+    name: &str,
+    extension: &str,
+) -> Vec<SpriteSheetFrame> {
+    let frame_width = file.header.width as usize;
+    let frame_height = file.header.height as usize;
+    let columns = grid_columns(file);
+
+    return file.frames.iter().enumerate().map(|(index, frame)| {
+        let origin = ((index % columns) * frame_width, (index / columns) * frame_height);
+
+        return SpriteSheetFrame {
+            // Named like the frames of a Json export of Aseprite
+            name: format!("{name} {index}.{extension}"),
+            rect: Rect::new(
+                origin.0 as f32,
+                origin.1 as f32,
+                (origin.0 + frame_width) as f32,
+                (origin.1 + frame_height) as f32,
+            ),
+            // Frames are never trimmed
+            source_size: Vec2::new(frame_width as f32, frame_height as f32),
+            duration: Some(frame.duration as f32 / 1000f32),
+            ..default()
+        };
+    }).collect();
+}
+
+// Composites every frame into a grid and returns the atlas image and the frames
+fn create_atlas(
+    file: &AsepriteFile,
+    name: &str,
+    extension: &str,
+) -> (Image, Vec<SpriteSheetFrame>) {
+    let frame_count = file.frames.len().max(1);
+    let columns = grid_columns(file);
+    let rows = frame_count.div_ceil(columns);
+
+    let atlas_width = columns * file.header.width as usize;
+    let atlas_height = rows * file.header.height as usize;
+
+    let mut pixels = vec![0u8; atlas_width * atlas_height * 4];
+    let frames = create_frames(file, name, extension);
+
+    let visible_layers = file.visible_layers();
+
+    for (index, frame) in frames.iter().enumerate() {
+        let origin = (frame.rect.min.x as usize, frame.rect.min.y as usize);
+
+        file.composite(index, &visible_layers, &mut pixels, atlas_width, origin);
+    }
+
+    let image = Image::new(
+        Extent3d {
+            width: atlas_width as u32,
+            height: atlas_height as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        pixels,
+        TextureFormat::Rgba8UnormSrgb,
+    );
+
+    return (image, frames);
+}
+
+// Parses an Aseprite file
+pub(crate) fn parse_file(
+    path: &Path,
+    bytes: &[u8],
+) -> Result<AsepriteFile, SpriteSheetError> {
+    let file = parse(bytes).map_err(|message| {
+        SpriteSheetError::Parse {
+            path: path.to_path_buf(),
+            line: None,
+            column: None,
+            message,
+        }
+    })?;
+
+    if file.header.frames == 0 || file.header.width == 0 || file.header.height == 0 {
+        return Err(SpriteSheetError::EmptySheet { path: path.to_path_buf() });
+    }
+
+    return Ok(file);
+}
+
+/// Asset loader for `.aseprite` and `.ase` files.
+///
+/// Loads Aseprite files as a [`SpriteSheet`](crate::SpriteSheet) without exporting them first.
+/// The visible layers of every frame are composited into an image on the CPU, using the normal
+/// blend mode for every layer, and the frames are laid out in a grid. Frames are named like in
+/// a Json export, e.g. `player 0.aseprite`, and keep their durations, tags and slices, so
+/// [`AnimationLibrary::from_sprite_sheet`](crate::AnimationLibrary::from_sprite_sheet) creates
+/// the same animations as for a Json export. Tilemap layers aren't supported.
+///
+/// The image is available as the labeled asset `image`, the texture atlas as `atlas`.
+#[derive(Debug, Default)]
+pub struct AsepriteLoader;
+
+impl AssetLoader for AsepriteLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let path = load_context.path();
+
+            let file = parse_file(path, bytes)?;
+
+            let name = path.file_stem().and_then(|name| name.to_str()).unwrap_or_default();
+            let extension = path.extension().and_then(|extension| extension.to_str()).unwrap_or_default();
+
+            let (image, frames) = create_atlas(&file, name, extension);
+
+            let texture = load_context.set_labeled_asset("image", LoadedAsset::new(image));
+
+            let texture_atlas = load_context.set_labeled_asset(
+                "atlas",
+                LoadedAsset::new(sprite_sheet::create_texture_atlas(texture.clone(), &frames)),
+            );
+
+            load_context.set_default_asset(
+                LoadedAsset::new(
+                    SpriteSheet {
+                        texture,
+                        texture_atlas: texture_atlas.clone(),
+                        pages: vec![texture_atlas],
+                        frames,
+                        tags: file.tags,
+                        slices: file.slices,
+                    }
+                )
+            );
+
+            return Ok(());
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        return &["aseprite", "ase"];
+    }
+}
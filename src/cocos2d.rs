@@ -0,0 +1,209 @@
+// Import necessary modules and crates
+use std::io::Cursor;
+use std::path::Path;
+
+use bevy::prelude::*;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::utils::BoxedFuture;
+
+use plist::{Dictionary, Value};
+
+use crate::AnimationLibrary;
+use crate::SpriteSheetError;
+use crate::sprite_sheet::{self, SpriteSheetFrame, SpriteSheetPage};
+
+// Parses the numbers of a string like {{x,y},{w,h}} or {x,y}
+fn parse_numbers(
+    value: &str,
+) -> Vec<f32> {
+    return value.split(['{', '}', ','])
+        .map(str::trim)
+        .filter(|number| !number.is_empty())
+        .filter_map(|number| number.parse().ok())
+        .collect();
+}
+
+// Reads a number that can be stored as an integer or a real
+fn number(
+    frame: &Dictionary,
+    key: &str,
+) -> f32 {
+    return match frame.get(key) {
+        Some(Value::Integer(number)) => number.as_signed().unwrap_or_default() as f32,
+        Some(Value::Real(number)) => *number as f32,
+        Some(Value::String(number)) => number.trim().parse().unwrap_or_default(),
+        _ => 0f32,
+    };
+}
+
+// Reads a string like {{x,y},{w,h}} as the position and size of a rect
+fn rect(
+    frame: &Dictionary,
+    key: &str,
+) -> Option<(Vec2, Vec2)> {
+    let numbers = parse_numbers(frame.get(key)?.as_string()?);
+
+    if numbers.len() < 4 {
+        return None;
+    }
+
+    return Some((Vec2::new(numbers[0], numbers[1]), Vec2::new(numbers[2], numbers[3])));
+}
+
+// Reads a string like {x,y} as a point
+fn point(
+    frame: &Dictionary,
+    key: &str,
+) -> Vec2 {
+    let numbers = frame.get(key).and_then(Value::as_string).map(parse_numbers).unwrap_or_default();
+
+    if numbers.len() < 2 {
+        return Vec2::ZERO;
+    }
+
+    return Vec2::new(numbers[0], numbers[1]);
+}
+
+fn boolean(
+    frame: &Dictionary,
+    key: &str,
+) -> bool {
+    return frame.get(key).and_then(Value::as_boolean).unwrap_or(false);
+}
+
+// Converts a frame of any of the formats 0 to 3 into a sprite sheet frame
+fn to_sprite_sheet_frame(
+    name: &str,
+    frame: &Dictionary,
+) -> Option<SpriteSheetFrame> {
+    let (position, size, rotated, offset, source_size) = if let Some((position, size)) = rect(frame, "textureRect") {
+        // Format 3
+        (
+            position,
+            size,
+            boolean(frame, "textureRotated"),
+            point(frame, "spriteOffset"),
+            point(frame, "spriteSourceSize"),
+        )
+    } else if let Some((position, size)) = rect(frame, "frame") {
+        // Formats 1 and 2
+        (position, size, boolean(frame, "rotated"), point(frame, "offset"), point(frame, "sourceSize"))
+    } else if frame.contains_key("x") {
+        // Format 0
+        (
+            Vec2::new(number(frame, "x"), number(frame, "y")),
+            Vec2::new(number(frame, "width"), number(frame, "height")),
+            false,
+            Vec2::new(number(frame, "offsetX"), number(frame, "offsetY")),
+            Vec2::new(number(frame, "originalWidth"), number(frame, "originalHeight")),
+        )
+    } else {
+        return None;
+    };
+
+    // The size is the size of the upright frame, rotated frames are stored with width and height swapped
+    let stored_size = if rotated { Vec2::new(size.y, size.x) } else { size };
+
+    return Some(
+        SpriteSheetFrame {
+            name: name.to_string(),
+            rect: Rect::from_corners(position, position + stored_size),
+            // The offset of the center of the frame from the center of the untrimmed frame, y up
+            offset,
+            // Frames without a source size aren't trimmed
+            source_size: if source_size == Vec2::ZERO { size } else { source_size },
+            pivot: None,
+            rotated,
+            counter_clockwise: false,
+            page: 0,
+            duration: None,
+        }
+    );
+}
+
+// Deserializes the plist content and converts the frames into sprite sheet frames
+pub(crate) fn parse(
+    path: &Path,
+    content: &[u8],
+) -> Result<(Option<String>, Vec<SpriteSheetFrame>), SpriteSheetError> {
+    let value = Value::from_reader(Cursor::new(content)).map_err(|error| SpriteSheetError::from_plist(path, error))?;
+
+    let root = value.as_dictionary().ok_or_else(|| SpriteSheetError::parse(path, "the root isn't a dictionary"))?;
+
+    let frames = root.get("frames").and_then(Value::as_dictionary)
+        .ok_or_else(|| SpriteSheetError::parse(path, "missing frames dictionary"))?;
+
+    let metadata = root.get("metadata").and_then(Value::as_dictionary);
+
+    let image_path = metadata.and_then(|metadata| {
+        return metadata.get("realTextureFileName")
+            .or_else(|| metadata.get("textureFileName"))
+            .and_then(Value::as_string);
+    }).map(str::to_string);
+
+    let mut sprite_sheet_frames = Vec::new();
+
+    for (name, frame) in frames.iter() {
+        let frame = frame.as_dictionary().and_then(|frame| to_sprite_sheet_frame(name, frame));
+
+        match frame {
+            Some(frame) => sprite_sheet_frames.push(frame),
+            None => return Err(SpriteSheetError::parse(path, &format!("frame ({name}) has no rect"))),
+        }
+    }
+
+    return Ok((image_path, sprite_sheet_frames));
+}
+
+pub fn create_animation_library(
+    path: &str,
+    texture_atlases: &mut Assets<TextureAtlas>,
+    asset_server: &AssetServer,
+) -> Result<AnimationLibrary, SpriteSheetError> {
+    let data_path = format!("{path}.plist");
+
+    let data_path = Path::new(&data_path);
+
+    // Load plist content from file, binary property lists aren't text
+    let content = sprite_sheet::read_data_bytes(asset_server, data_path)?;
+
+    let pages = SpriteSheetPage::single(parse(data_path, &content)?);
+
+    return sprite_sheet::create_animation_library(
+        data_path,
+        pages,
+        texture_atlases,
+        asset_server,
+    );
+}
+
+/// Asset loader for the Cocos2d-x data format.
+///
+/// Loads `.plist` files in the formats 0 to 3 as a [`SpriteSheet`](crate::SpriteSheet). The
+/// image is loaded as a dependency using `metadata.realTextureFileName` or
+/// `metadata.textureFileName`, relative to the data file. If both are missing, a `.png` with
+/// the same name as the data file is used instead.
+///
+/// The texture atlas is available as the labeled asset `atlas`.
+#[derive(Debug, Default)]
+pub struct Cocos2dLoader;
+
+impl AssetLoader for Cocos2dLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let pages = SpriteSheetPage::single(parse(load_context.path(), bytes)?);
+
+            sprite_sheet::set_sprite_sheet_asset(load_context, pages, Vec::new(), Vec::new())?;
+
+            return Ok(());
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        return &["plist"];
+    }
+}
@@ -0,0 +1,265 @@
+// Import necessary modules and crates
+use std::collections::{HashMap, HashSet};
+
+use bevy::prelude::*;
+use bevy::asset::HandleId;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+
+use crate::AnimationLibrary;
+use crate::animate_atlas::part_bounds;
+use crate::animation_library::FramePart;
+
+// A texture atlas whose frames are composed of the parts of other texture atlases
+#[derive(Debug, Default)]
+struct ComposedAtlas {
+    // Index of every frame in the texture atlas and its parts, from the bottom to the top
+    frames: Vec<(usize, Vec<FramePart>)>,
+    part_atlases: Vec<Handle<TextureAtlas>>,
+    // The image the frames are drawn into
+    baked: Option<Handle<Image>>,
+}
+
+// The texture atlases whose frames are drawn by bake_composed_frames
+#[derive(Debug, Default, Resource)]
+pub(crate) struct ComposedAtlases {
+    atlases: HashMap<HandleId, ComposedAtlas>,
+}
+
+impl ComposedAtlases {
+    // Adds the composed frames of the library
+    fn register(
+        &mut self,
+        library: &AnimationLibrary,
+    ) {
+        let frames: Vec<(usize, Vec<FramePart>)> = library.composed_frames()
+            .map(|(index, parts)| (index, parts.to_vec()))
+            .collect();
+
+        if frames.is_empty() {
+            return;
+        }
+
+        let composed_atlas = self.atlases.entry(library.texture_atlas.id()).or_default();
+
+        if composed_atlas.frames != frames || composed_atlas.part_atlases != library.part_atlases() {
+            composed_atlas.frames = frames;
+            composed_atlas.part_atlases = library.part_atlases().to_vec();
+            // The frames have to be drawn again
+            composed_atlas.baked = None;
+        }
+    }
+}
+
+// Blends a pixel over another one, both with straight alpha
+fn blend(
+    target: &mut [u8],
+    source: &[u8],
+) {
+    let source_alpha = source[3] as f32 / 255f32;
+
+    if source_alpha <= 0f32 {
+        return;
+    }
+
+    let target_alpha = target[3] as f32 / 255f32 * (1f32 - source_alpha);
+    let alpha = source_alpha + target_alpha;
+
+    for channel in 0..3 {
+        let color = (source[channel] as f32 * source_alpha + target[channel] as f32 * target_alpha) / alpha;
+        target[channel] = color.round() as u8;
+    }
+
+    target[3] = (alpha * 255f32).round() as u8;
+}
+
+// Draws the composed frames into a new image the size of the texture atlas
+//
+// Returns None if the parts aren't stored in 8 bit RGBA images, the only format that is blended
+fn bake_frames(
+    texture_atlas: &TextureAtlas,
+    frames: &[(usize, Vec<FramePart>)],
+    part_atlases: &[&TextureAtlas],
+    part_images: &[&Image],
+) -> Option<Image> {
+    let format = part_images.first().map_or(TextureFormat::Rgba8UnormSrgb, |image| image.texture_descriptor.format);
+
+    let is_supported = |image: &&Image| {
+        image.texture_descriptor.format == format
+            && image.data.len() >= image.texture_descriptor.size.width as usize * image.texture_descriptor.size.height as usize * 4
+    };
+
+    if !matches!(format, TextureFormat::Rgba8UnormSrgb | TextureFormat::Rgba8Unorm) || !part_images.iter().all(is_supported) {
+        return None;
+    }
+
+    let width = texture_atlas.size.x as usize;
+    let height = texture_atlas.size.y as usize;
+
+    let mut data = vec![0u8; width * height * 4];
+
+    for (index, parts) in frames.iter() {
+        let Some(rect) = texture_atlas.textures.get(*index) else {
+            continue;
+        };
+
+        // The parts are placed relative to the origin of the frame, the top left corner of the
+        // rect is the top left corner of the area covered by the parts
+        let bounds = part_bounds(parts);
+
+        for part in parts.iter() {
+            // Parts that are squashed into a line don't cover any pixel
+            if part.transform.matrix2.determinant() == 0f32 {
+                continue;
+            }
+
+            let inverse = part.transform.inverse();
+
+            let (Some(part_atlas), Some(image)) = (part_atlases.get(part.atlas), part_images.get(part.atlas)) else {
+                continue;
+            };
+
+            let Some(part_rect) = part_atlas.textures.get(part.index) else {
+                continue;
+            };
+
+            let image_width = image.texture_descriptor.size.width as usize;
+            let image_height = image.texture_descriptor.size.height as usize;
+
+            // Only the pixels of the area covered by the part are drawn
+            let covered = part_bounds(std::slice::from_ref(part));
+
+            let (min_x, max_x) = ((covered.min.x - bounds.min.x) as usize, (covered.max.x - bounds.min.x) as usize);
+            let (min_y, max_y) = ((bounds.max.y - covered.max.y) as usize, (bounds.max.y - covered.min.y) as usize);
+
+            for y in min_y..max_y.min(rect.height() as usize) {
+                for x in min_x..max_x.min(rect.width() as usize) {
+                    // Center of the pixel relative to the origin of the frame, y up
+                    let point = Vec2::new(bounds.min.x + x as f32 + 0.5, bounds.max.y - y as f32 - 0.5);
+
+                    // Position in the part relative to its top left corner, y down
+                    let local = inverse.transform_point2(point);
+                    let (part_x, part_y) = (local.x, -local.y);
+
+                    if part_x < 0f32 || part_y < 0f32 || part_x >= part.size.x || part_y >= part.size.y {
+                        continue;
+                    }
+
+                    let source_x = part_rect.min.x as usize + part_x as usize;
+                    let source_y = part_rect.min.y as usize + part_y as usize;
+
+                    if source_x >= image_width || source_y >= image_height {
+                        continue;
+                    }
+
+                    let source = (source_y * image_width + source_x) * 4;
+                    let target = ((rect.min.y as usize + y) * width + rect.min.x as usize + x) * 4;
+
+                    blend(&mut data[target..target + 4], &image.data[source..source + 4]);
+                }
+            }
+        }
+    }
+
+    let mut baked = Image::new(
+        Extent3d {
+            width: width as u32,
+            height: height as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        format,
+    );
+
+    if let Some(image) = part_images.first() {
+        baked.sampler_descriptor = image.sampler_descriptor.clone();
+    }
+
+    return Some(baked);
+}
+
+// System drawing the frames of Adobe Animate texture atlases
+//
+// The frames are composed of parts placed with any affine transform, including shear. Once the
+// images of the parts are loaded, every frame is drawn into a new image used by the texture
+// atlas of the library, so it is shown like any other frame. The frames are drawn again when an
+// image of the parts or the library is reloaded, it is added by the SpriteSheetAnimationPlugin
+pub(crate) fn bake_composed_frames(
+    mut composed_atlases: ResMut<ComposedAtlases>,
+    mut library_events: EventReader<AssetEvent<AnimationLibrary>>,
+    mut texture_atlas_events: EventReader<AssetEvent<TextureAtlas>>,
+    mut image_events: EventReader<AssetEvent<Image>>,
+    libraries: Res<Assets<AnimationLibrary>>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    for event in library_events.iter() {
+        if let AssetEvent::Created { handle } | AssetEvent::Modified { handle } = event {
+            if let Some(library) = libraries.get(handle) {
+                composed_atlases.register(library);
+            }
+        }
+    }
+
+    for event in texture_atlas_events.iter() {
+        if let AssetEvent::Removed { handle } = event {
+            composed_atlases.atlases.remove(&handle.id());
+        }
+    }
+
+    let modified_images: HashSet<HandleId> = image_events.iter().filter_map(|event| match event {
+        AssetEvent::Modified { handle } => Some(handle.id()),
+        _ => None,
+    }).collect();
+
+    for (id, composed_atlas) in composed_atlases.atlases.iter_mut() {
+        let Some(texture_atlas) = texture_atlases.get(&Handle::weak(*id)) else {
+            continue;
+        };
+
+        let part_atlases: Option<Vec<&TextureAtlas>> = composed_atlas.part_atlases.iter()
+            .map(|part_atlas| texture_atlases.get(part_atlas))
+            .collect();
+
+        let Some(part_atlases) = part_atlases else {
+            continue;
+        };
+
+        let is_baked = composed_atlas.baked.as_ref() == Some(&texture_atlas.texture);
+
+        // Draw the frames again if an image of the parts was reloaded
+        if is_baked && !part_atlases.iter().any(|part_atlas| modified_images.contains(&part_atlas.texture.id())) {
+            continue;
+        }
+
+        // Wait for the images of the parts to be loaded
+        let part_images: Option<Vec<&Image>> = part_atlases.iter()
+            .map(|part_atlas| images.get(&part_atlas.texture))
+            .collect();
+
+        let Some(part_images) = part_images else {
+            continue;
+        };
+
+        let Some(baked_image) = bake_frames(texture_atlas, &composed_atlas.frames, &part_atlases, &part_images) else {
+            println!("\x1b[38;5;196mFrames can only be composed of parts stored in RGBA images\x1b[0;0;0m");
+            composed_atlas.baked = Some(texture_atlas.texture.clone());
+            continue;
+        };
+
+        // Until the frames are drawn, the texture atlas uses the default handle
+        let baked = match composed_atlas.baked.clone() {
+            Some(baked) if baked != Handle::default() => {
+                images.set_untracked(baked.clone(), baked_image);
+                baked
+            },
+            _ => images.add(baked_image),
+        };
+
+        composed_atlas.baked = Some(baked.clone());
+
+        if let Some(texture_atlas) = texture_atlases.get_mut(&Handle::weak(*id)) {
+            texture_atlas.texture = baked;
+        }
+    }
+}
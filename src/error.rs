@@ -0,0 +1,203 @@
+// Import necessary modules and crates
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use bevy::asset::AssetIoError;
+use xml::common::Position;
+
+/// Error returned when a sprite sheet couldn't be loaded.
+///
+/// Every variant contains the path of the file that caused the error, so it can be
+/// reported back to whoever created the sprite sheet.
+#[derive(Debug)]
+pub enum SpriteSheetError {
+    /// The data file couldn't be read.
+    Io {
+        /// The path of the data file.
+        path: PathBuf,
+        /// The underlying IO error.
+        source: std::io::Error,
+    },
+    /// The data file couldn't be parsed.
+    Parse {
+        /// The path of the data file.
+        path: PathBuf,
+        /// The line the error occurred on, starting at 1, if known.
+        line: Option<usize>,
+        /// The column the error occurred on, starting at 1, if known.
+        column: Option<usize>,
+        /// A description of what went wrong.
+        message: String,
+    },
+    /// The image referenced by the data file doesn't exist.
+    MissingImage {
+        /// The path of the data file.
+        path: PathBuf,
+        /// The path of the image that couldn't be found.
+        image_path: PathBuf,
+    },
+    /// The data file doesn't contain any frames.
+    EmptySheet {
+        /// The path of the data file.
+        path: PathBuf,
+    },
+}
+
+impl SpriteSheetError {
+    /// Returns the path of the file that caused the error.
+    pub fn path(
+        &self
+    ) -> &Path {
+        return match self {
+            SpriteSheetError::Io { path, .. } => path,
+            SpriteSheetError::Parse { path, .. } => path,
+            SpriteSheetError::MissingImage { path, .. } => path,
+            SpriteSheetError::EmptySheet { path } => path,
+        };
+    }
+
+    // Creates an IO error from an error of the AssetIo
+    pub(crate) fn from_asset_io(
+        path: &Path,
+        error: AssetIoError,
+    ) -> Self {
+        let source = match error {
+            AssetIoError::Io(source) => source,
+            AssetIoError::NotFound(_) => std::io::Error::new(std::io::ErrorKind::NotFound, error),
+            error => std::io::Error::other(error),
+        };
+
+        return SpriteSheetError::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+    }
+
+    // Creates a parse error from a Json error
+    pub(crate) fn from_json(
+        path: &Path,
+        error: serde_json::Error,
+    ) -> Self {
+        // serde_json reports line 0 if the error isn't tied to a location
+        let has_location = error.line() > 0;
+
+        // Strip the location from the message, since it is stored separately
+        let message = error.to_string();
+        let suffix = format!(" at line {} column {}", error.line(), error.column());
+        let message = message.strip_suffix(&suffix).unwrap_or(&message).to_string();
+
+        return SpriteSheetError::Parse {
+            path: path.to_path_buf(),
+            line: has_location.then(|| error.line()),
+            column: has_location.then(|| error.column()),
+            message,
+        };
+    }
+
+    // Creates a parse error from a RON error
+    pub(crate) fn from_ron(
+        path: &Path,
+        error: ron::error::SpannedError,
+    ) -> Self {
+        return SpriteSheetError::Parse {
+            path: path.to_path_buf(),
+            line: Some(error.position.line),
+            column: Some(error.position.col),
+            message: error.code.to_string(),
+        };
+    }
+
+    // Creates a parse error from a plist error
+    pub(crate) fn from_plist(
+        path: &Path,
+        error: plist::Error,
+    ) -> Self {
+        return SpriteSheetError::Parse {
+            path: path.to_path_buf(),
+            line: None,
+            column: None,
+            message: error.to_string(),
+        };
+    }
+
+    // Creates a parse error without a location
+    pub(crate) fn parse(
+        path: &Path,
+        message: &str,
+    ) -> Self {
+        return SpriteSheetError::Parse {
+            path: path.to_path_buf(),
+            line: None,
+            column: None,
+            message: message.to_string(),
+        };
+    }
+
+    // Creates a parse error from a XML error
+    pub(crate) fn from_xml(
+        path: &Path,
+        error: serde_xml_rs::Error,
+    ) -> Self {
+        return match error {
+            serde_xml_rs::Error::Syntax { source } => {
+                let position = source.position();
+
+                SpriteSheetError::Parse {
+                    path: path.to_path_buf(),
+                    line: Some(position.row as usize + 1),
+                    column: Some(position.column as usize + 1),
+                    message: source.msg().to_string(),
+                }
+            }
+            error => SpriteSheetError::Parse {
+                path: path.to_path_buf(),
+                line: None,
+                column: None,
+                message: error.to_string(),
+            },
+        };
+    }
+}
+
+impl fmt::Display for SpriteSheetError {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        return match self {
+            SpriteSheetError::Io { path, source } => {
+                write!(f, "couldn't read {}: {source}", path.display())
+            }
+            SpriteSheetError::Parse { path, line, column, message } => {
+                write!(f, "couldn't parse {}", path.display())?;
+
+                if let Some(line) = line {
+                    write!(f, " at line {line}")?;
+                }
+
+                if let Some(column) = column {
+                    write!(f, ", column {column}")?;
+                }
+
+                write!(f, ": {message}")
+            }
+            SpriteSheetError::MissingImage { path, image_path } => {
+                write!(f, "the image {} referenced by {} doesn't exist", image_path.display(), path.display())
+            }
+            SpriteSheetError::EmptySheet { path } => {
+                write!(f, "{} doesn't contain any frames", path.display())
+            }
+        };
+    }
+}
+
+impl std::error::Error for SpriteSheetError {
+    fn source(
+        &self
+    ) -> Option<&(dyn std::error::Error + 'static)> {
+        return match self {
+            SpriteSheetError::Io { source, .. } => Some(source),
+            _ => None,
+        };
+    }
+}
@@ -0,0 +1,178 @@
+// Import necessary modules and crates
+use bevy::prelude::*;
+use bevy::ecs::system::SystemParam;
+
+use crate::AnimationData;
+
+/// Event sent when an animation starts playing on an `AnimatedSprite`.
+///
+/// # Fields
+///
+/// - `entity`: The entity of the `AnimatedSprite`.
+/// - `animation`: The name of the animation.
+#[derive(Debug, Clone)]
+pub struct AnimationStarted {
+    /// The entity of the `AnimatedSprite`.
+    pub entity: Entity,
+    /// The name of the animation.
+    pub animation: String,
+}
+
+/// Event sent when an animation starts another pass instead of finishing.
+///
+/// Looped animations jump back to their `loop_start`, or to their last frame when they are
+/// played backwards, and ping-pong animations turn around at either end.
+///
+/// # Fields
+///
+/// - `entity`: The entity of the `AnimatedSprite`.
+/// - `animation`: The name of the animation.
+#[derive(Debug, Clone)]
+pub struct AnimationLooped {
+    /// The entity of the `AnimatedSprite`.
+    pub entity: Entity,
+    /// The name of the animation.
+    pub animation: String,
+}
+
+/// Event sent when an animation has finished playing, after the last pass of its loop mode.
+///
+/// # Fields
+///
+/// - `entity`: The entity of the `AnimatedSprite`.
+/// - `animation`: The name of the animation.
+#[derive(Debug, Clone)]
+pub struct AnimationFinished {
+    /// The entity of the `AnimatedSprite`.
+    pub entity: Entity,
+    /// The name of the animation.
+    pub animation: String,
+}
+
+/// Event sent when the frame of an `AnimatedSprite` changes, including the first frame
+/// when an animation starts.
+///
+/// # Fields
+///
+/// - `entity`: The entity of the `AnimatedSprite`.
+/// - `animation`: The name of the animation.
+/// - `frame`: The index of the new frame within the animation.
+#[derive(Debug, Clone)]
+pub struct FrameChanged {
+    /// The entity of the `AnimatedSprite`.
+    pub entity: Entity,
+    /// The name of the animation.
+    pub animation: String,
+    /// The index of the new frame within the animation.
+    pub frame: usize,
+}
+
+/// Event sent when the `AnimationLibrary` of an `AnimatedSprite` was modified or reloaded,
+/// e.g. because its data file or definition file was changed.
+///
+/// The current animation keeps playing if it still exists, frames that don't exist anymore
+/// are clamped to its last frame.
+///
+/// # Fields
+///
+/// - `entity`: The entity of the `AnimatedSprite`.
+/// - `animation`: The name of the current animation, or `None` if no animation is playing
+///   or it doesn't exist anymore.
+#[derive(Debug, Clone)]
+pub struct AnimationReloaded {
+    /// The entity of the `AnimatedSprite`.
+    pub entity: Entity,
+    /// The name of the current animation, or `None` if no animation is playing or it
+    /// doesn't exist anymore.
+    pub animation: Option<String>,
+}
+
+/// Event sent when playback enters a frame with a marker.
+///
+/// # Fields
+///
+/// - `entity`: The entity of the `AnimatedSprite`.
+/// - `animation`: The name of the animation.
+/// - `frame`: The index of the frame within the animation.
+/// - `name`: The name of the marker.
+/// - `payload`: Additional data of the marker.
+#[derive(Debug, Clone)]
+pub struct AnimationEvent {
+    /// The entity of the `AnimatedSprite`.
+    pub entity: Entity,
+    /// The name of the animation.
+    pub animation: String,
+    /// The index of the frame within the animation.
+    pub frame: usize,
+    /// The name of the marker.
+    pub name: String,
+    /// Additional data of the marker.
+    pub payload: Option<String>,
+}
+
+/// System parameter used by `update_animations` to send the animation events.
+#[derive(SystemParam)]
+pub struct AnimationEventWriters<'w, 's> {
+    started: EventWriter<'w, 's, AnimationStarted>,
+    looped: EventWriter<'w, 's, AnimationLooped>,
+    finished: EventWriter<'w, 's, AnimationFinished>,
+    frame_changed: EventWriter<'w, 's, FrameChanged>,
+    markers: EventWriter<'w, 's, AnimationEvent>,
+    reloaded: EventWriter<'w, 's, AnimationReloaded>,
+}
+
+impl<'w, 's> AnimationEventWriters<'w, 's> {
+    pub(crate) fn started(
+        &mut self,
+        entity: Entity,
+        animation: &str,
+    ) {
+        self.started.send(AnimationStarted { entity, animation: animation.to_string() });
+    }
+
+    pub(crate) fn looped(
+        &mut self,
+        entity: Entity,
+        animation: &str,
+    ) {
+        self.looped.send(AnimationLooped { entity, animation: animation.to_string() });
+    }
+
+    pub(crate) fn finished(
+        &mut self,
+        entity: Entity,
+        animation: &str,
+    ) {
+        self.finished.send(AnimationFinished { entity, animation: animation.to_string() });
+    }
+
+    pub(crate) fn reloaded(
+        &mut self,
+        entity: Entity,
+        animation: Option<&str>,
+    ) {
+        self.reloaded.send(AnimationReloaded { entity, animation: animation.map(str::to_string) });
+    }
+
+    // Sends the FrameChanged event and the events of the markers on the frame
+    pub(crate) fn frame_changed(
+        &mut self,
+        entity: Entity,
+        animation: &AnimationData,
+        frame: usize,
+    ) {
+        self.frame_changed.send(FrameChanged { entity, animation: animation.name.clone(), frame });
+
+        for event in animation.frame_events(frame) {
+            self.markers.send(
+                AnimationEvent {
+                    entity,
+                    animation: animation.name.clone(),
+                    frame,
+                    name: event.name.clone(),
+                    payload: event.payload.clone(),
+                }
+            );
+        }
+    }
+}
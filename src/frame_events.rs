@@ -0,0 +1,86 @@
+// Import necessary modules and crates
+use std::collections::HashMap;
+
+use bevy::asset::{AssetLoader, LoadContext, LoadedAsset};
+use bevy::reflect::TypeUuid;
+use bevy::utils::BoxedFuture;
+
+use serde::Deserialize;
+
+use crate::SpriteSheetError;
+
+/// Struct representing a named marker on a frame of an animation.
+///
+/// Whenever playback enters the frame, an `AnimationEvent` containing the name and payload
+/// of the marker is sent.
+///
+/// # Fields
+///
+/// - `frame`: The index of the frame within the animation.
+/// - `name`: The name of the marker, e.g. `footstep`.
+/// - `payload`: Additional data sent with the event.
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct FrameEvent {
+    /// The index of the frame within the animation.
+    pub frame: usize,
+    /// The name of the marker, e.g. `footstep`.
+    pub name: String,
+    /// Additional data sent with the event.
+    #[serde(default)]
+    pub payload: Option<String>,
+}
+
+/// Asset containing frame markers for the animations of an `AnimationLibrary`.
+///
+/// It is loaded from a sidecar `.events.json` file that maps animation names to their markers:
+///
+/// ```json
+/// {
+///     "walk": [
+///         { "frame": 3, "name": "footstep" },
+///         { "frame": 9, "name": "footstep" }
+///     ],
+///     "attack": [
+///         { "frame": 5, "name": "hit_active", "payload": "sword" }
+///     ]
+/// }
+/// ```
+///
+/// The markers are added to the animations with `AnimationLibrary::add_frame_events`.
+#[derive(Debug, Default, Clone, Deserialize, TypeUuid)]
+#[uuid = "9a7c5e2d-4f1b-4b6a-8c3e-2d1f0a9b8c7e"]
+pub struct FrameEvents {
+    /// The markers of each animation, by animation name.
+    #[serde(flatten)]
+    pub animations: HashMap<String, Vec<FrameEvent>>,
+}
+
+/// Asset loader for `.events.json` files containing `FrameEvents`.
+#[derive(Debug, Default)]
+pub struct FrameEventsLoader;
+
+impl AssetLoader for FrameEventsLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let content = std::str::from_utf8(bytes)?;
+
+            // Remove the BOM if present (UTF-8 BOM is 0xEF, 0xBB, 0xBF)
+            let content = content.trim_start_matches('\u{FEFF}');
+
+            let frame_events: FrameEvents = serde_json::from_str(content)
+                .map_err(|error| SpriteSheetError::from_json(load_context.path(), error))?;
+
+            load_context.set_default_asset(LoadedAsset::new(frame_events));
+
+            return Ok(());
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        return &["events.json"];
+    }
+}
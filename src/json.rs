@@ -1,109 +1,386 @@
 // Import necessary modules and crates
+use std::fmt;
+use std::path::{Path, PathBuf};
+
 use bevy::prelude::*;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::utils::BoxedFuture;
+
+use serde::{Deserialize, Deserializer};
+use serde::de::{MapAccess, Visitor};
+
+use crate::AnimationLibrary;
+use crate::SpriteSheetError;
+use crate::animate_atlas;
+use crate::aseprite;
+use crate::json_array;
+use crate::phaser;
+use crate::sprite_sheet::{self, SpriteSheetFrame, SpriteSheetPage};
 
-use serde::Deserialize;
-use serde_json;
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct Frame {
+    pub(crate) x: u32,
+    pub(crate) y: u32,
+    pub(crate) w: u32,
+    pub(crate) h: u32,
+}
 
-use crate::AnimatedSpriteBundle;
-use crate::AnimatedSprite;
-use crate::FrameOffset;
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct SpriteSourceSize {
+    pub(crate) x: u32,
+    pub(crate) y: u32,
+    #[serde(default)]
+    pub(crate) w: Option<u32>,
+    #[serde(default)]
+    pub(crate) h: Option<u32>,
+}
 
 #[derive(Debug, Default, Deserialize)]
-struct Frame {
-    x: u32,
-    y: u32,
-    w: u32,
-    h: u32,
+pub(crate) struct SourceSize {
+    pub(crate) w: u32,
+    pub(crate) h: u32,
 }
 
+// Position of the pivot in the untrimmed frame, from 0 to 1 with y pointing down
 #[derive(Debug, Default, Deserialize)]
-struct SpriteSourceSize {
-    x: u32,
-    y: u32,
-    // we dont need these
-    // w: u32,
-    // h: u32,
+pub(crate) struct Pivot {
+    pub(crate) x: f32,
+    pub(crate) y: f32,
 }
 
 #[derive(Debug, Default, Deserialize)]
-struct FrameData {
-    frame: Frame,
-    rotated: bool,
+pub(crate) struct FrameData {
+    pub(crate) frame: Frame,
+    pub(crate) rotated: bool,
     #[serde(rename = "spriteSourceSize")]
-    sprite_source_size: SpriteSourceSize,
+    pub(crate) sprite_source_size: SpriteSourceSize,
+    // size of the untrimmed frame
+    #[serde(default, rename = "sourceSize")]
+    pub(crate) source_size: Option<SourceSize>,
+    #[serde(default)]
+    pub(crate) pivot: Option<Pivot>,
+    // duration of the frame in milliseconds
+    #[serde(default)]
+    pub(crate) duration: Option<f32>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct Meta {
+    pub(crate) image: Option<String>,
+    // The other data files of a TexturePacker multipack
+    #[serde(default)]
+    pub(crate) related_multi_packs: Vec<String>,
 }
 
 #[derive(Debug, Default, Deserialize)]
 struct Frames {
     // Each frame name will be a field in this struct
-    // Use a BTreeMap to preserve the order of frames
-    frames: std::collections::BTreeMap<String, FrameData>,
+    // The frames are kept in the order of the data file, frame tags refer to them by index
+    #[serde(deserialize_with = "deserialize_frames")]
+    frames: Vec<(String, FrameData)>,
+    #[serde(default)]
+    meta: Meta,
+}
+
+// Deserializes the frames hash into the frame names and data, in the order of the data file
+fn deserialize_frames<'de, D>(
+    deserializer: D,
+) -> Result<Vec<(String, FrameData)>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct FramesVisitor;
+
+    impl<'de> Visitor<'de> for FramesVisitor {
+        type Value = Vec<(String, FrameData)>;
+
+        fn expecting(
+            &self,
+            formatter: &mut fmt::Formatter,
+        ) -> fmt::Result {
+            return formatter.write_str("a map of frame names to frames");
+        }
+
+        fn visit_map<A>(
+            self,
+            mut map: A,
+        ) -> Result<Self::Value, A::Error>
+        where
+            A: MapAccess<'de>,
+        {
+            let mut frames = Vec::new();
+
+            while let Some(frame) = map.next_entry()? {
+                frames.push(frame);
+            }
+
+            return Ok(frames);
+        }
+    }
+
+    return deserializer.deserialize_map(FramesVisitor);
+}
+
+impl FrameData {
+    // Returns the size of the untrimmed frame
+    fn source_size(
+        &self
+    ) -> Vec2 {
+        return match &self.source_size {
+            Some(source_size) => Vec2::new(source_size.w as f32, source_size.h as f32),
+            None => Vec2::new(self.frame.w as f32, self.frame.h as f32),
+        };
+    }
+
+    // Returns the offset of the center of the frame from the center of the untrimmed frame, y up
+    fn offset(
+        &self
+    ) -> Vec2 {
+        // spriteSourceSize is the position and size of the trimmed frame in the untrimmed frame
+        let size = Vec2::new(
+            self.sprite_source_size.w.unwrap_or(self.frame.w) as f32,
+            self.sprite_source_size.h.unwrap_or(self.frame.h) as f32,
+        );
+
+        let position = Vec2::new(self.sprite_source_size.x as f32, self.sprite_source_size.y as f32);
+
+        let offset = position + (size - self.source_size()) * 0.5;
+
+        return Vec2::new(offset.x, -offset.y);
+    }
+
+    // Converts the frame data into a sprite sheet frame
+    pub(crate) fn to_sprite_sheet_frame(
+        &self,
+        name: &str,
+    ) -> SpriteSheetFrame {
+        // The size is the size of the upright frame, rotated frames are stored with width and height swapped
+        let (width, height) = if self.rotated { (self.frame.h, self.frame.w) } else { (self.frame.w, self.frame.h) };
+
+        return SpriteSheetFrame {
+            name: name.to_string(),
+            rect: Rect::new(
+                self.frame.x as f32,
+                self.frame.y as f32,
+                (self.frame.x + width) as f32,
+                (self.frame.y + height) as f32,
+            ),
+            offset: self.offset(),
+            source_size: self.source_size(),
+            pivot: self.pivot.as_ref().map(|pivot| Vec2::new(pivot.x - 0.5, 0.5 - pivot.y)),
+            rotated: self.rotated,
+            counter_clockwise: false,
+            page: 0,
+            duration: self.duration.map(|duration| duration / 1000f32),
+        };
+    }
+}
+
+// Deserializes the Json content and converts the frames into sprite sheet frames
+fn parse(
+    path: &Path,
+    content: &str,
+) -> Result<(Option<String>, Vec<SpriteSheetFrame>), SpriteSheetError> {
+    // Remove the BOM if present (UTF-8 BOM is 0xEF, 0xBB, 0xBF)
+    let content = content.trim_start_matches('\u{FEFF}');
+
+    // Deserialize Json data
+    let json_data: Frames = serde_json::from_str(content).map_err(|error| SpriteSheetError::from_json(path, error))?;
+
+    let frames = json_data.frames.iter().map(|(name, frame)| frame.to_sprite_sheet_frame(name)).collect();
+
+    return Ok((json_data.meta.image, frames));
 }
 
-pub fn create_animated_sprite_bundle(
+pub fn create_animation_library(
     path: &str,
     texture_atlases: &mut Assets<TextureAtlas>,
     asset_server: &AssetServer,
-) -> Option<AnimatedSpriteBundle> {
+) -> Result<AnimationLibrary, SpriteSheetError> {
+    let data_path = format!("{path}.json");
+
+    let data_path = Path::new(&data_path);
+
     // Load Json content from file
-    let content = std::fs::read_to_string(format!("assets/{path}.json")).ok()?;
+    let content = sprite_sheet::read_data_file(asset_server, data_path)?;
 
-    // Remove the BOM if present (UTF-8 BOM is 0xEF, 0xBB, 0xBF)
-    let content = content.trim_start_matches('\u{FEFF}').to_string();
+    let mut pages = SpriteSheetPage::single(parse(data_path, &content)?);
+    pages.extend(read_multi_packs(asset_server, data_path, &content)?);
 
-    // Deserialize Json data
-    let json_data: Frames = serde_json::from_str(&content).ok()?;
-
-    // Load texture atlas and prepare sprite sheet bundle
-    let texture_atlas_handle = texture_atlases.add(
-        TextureAtlas::new_empty(
-            asset_server.load(format!("{path}.png")), 
-            Vec2::default()
-        )
+    return sprite_sheet::create_animation_library(
+        data_path,
+        pages,
+        texture_atlases,
+        asset_server,
     );
+}
 
-    let texture_atlas = texture_atlases.get_mut(&texture_atlas_handle)?;
-
-    // Prepare animated sprite data
-    let mut animated_sprite = AnimatedSprite::default();
-
-    // Add frames to the texture atlas and animated sprite data
-    for frame in json_data.frames.iter() {
-        // Add texture to atlas
-        let index = texture_atlas.add_texture(
-            Rect::new(
-                frame.1.frame.x as f32,
-                frame.1.frame.y as f32,
-                (frame.1.frame.x + frame.1.frame.w) as f32,
-                (frame.1.frame.y + frame.1.frame.h) as f32,
-            )
-        );
+// Layouts of the frames of Json content
+enum Layout {
+    Hash,
+    Array,
+    MultiAtlas,
+}
 
-        // Insert texture index into frames and set frame offset
-        animated_sprite.frames.insert(
-            frame.0.clone(),
-            index
-        );
-        
-        animated_sprite.frame_offsets.insert(
-            index,
-            FrameOffset {
-                position_offset: Vec2::new(
-                    frame.1.sprite_source_size.x as f32 * -0.5, // negative because for some reason
-                    frame.1.sprite_source_size.y as f32 * -0.5, // the json has the inverted sign
-                ),
-                rotation_offset: if frame.1.rotated {std::f32::consts::PI * 0.5} else {0f32},
+// Parses Json content using the Json, the Json Array or the Phaser 3 layout, depending on its frames
+pub(crate) fn parse_layout(
+    path: &Path,
+    content: &str,
+) -> Result<Vec<SpriteSheetPage>, SpriteSheetError> {
+    return match detect_layout(path, content)? {
+        Layout::Hash => parse(path, content).map(SpriteSheetPage::single),
+        Layout::Array => json_array::parse(path, content).map(SpriteSheetPage::single),
+        Layout::MultiAtlas => phaser::parse(path, content),
+    };
+}
+
+// Returns the paths of the other data files of a TexturePacker multipack, relative to the assets folder
+pub(crate) fn related_multi_packs(
+    path: &Path,
+    content: &str,
+) -> Result<Vec<PathBuf>, SpriteSheetError> {
+    #[derive(Deserialize)]
+    struct MetaData {
+        #[serde(default)]
+        meta: Meta,
+    }
+
+    let meta_data: MetaData = serde_json::from_str(content.trim_start_matches('\u{FEFF}'))
+        .map_err(|error| SpriteSheetError::from_json(path, error))?;
+
+    let directory = path.parent().unwrap_or(Path::new(""));
+
+    return Ok(meta_data.meta.related_multi_packs.iter().map(|related| directory.join(related)).collect());
+}
+
+// Reads the pages of the other data files of a TexturePacker multipack from the assets folder
+pub(crate) fn read_multi_packs(
+    asset_server: &AssetServer,
+    path: &Path,
+    content: &str,
+) -> Result<Vec<SpriteSheetPage>, SpriteSheetError> {
+    let mut pages = Vec::new();
+
+    for related_path in related_multi_packs(path, content)? {
+        let related_content = sprite_sheet::read_data_file(asset_server, &related_path)?;
+
+        // The images of the pages are relative to the data file that is loaded
+        pages.extend(relative_to(path, &related_path, parse_layout(&related_path, &related_content)?));
+    }
+
+    return Ok(pages);
+}
+
+// Loads the pages of the other data files of a TexturePacker multipack through the load context
+pub(crate) async fn load_multi_packs(
+    load_context: &LoadContext<'_>,
+    path: &Path,
+    content: &str,
+) -> Result<Vec<SpriteSheetPage>, bevy::asset::Error> {
+    let mut pages = Vec::new();
+
+    for related_path in related_multi_packs(path, content)? {
+        let related_content = load_context.read_asset_bytes(&related_path).await
+            .map_err(|error| SpriteSheetError::from_asset_io(&related_path, error))?;
+
+        let related_content = std::str::from_utf8(&related_content)?;
+
+        pages.extend(relative_to(path, &related_path, parse_layout(&related_path, related_content)?));
+    }
+
+    return Ok(pages);
+}
+
+// Makes the image paths of the pages of a related data file relative to the data file
+fn relative_to(
+    path: &Path,
+    related_path: &Path,
+    pages: Vec<SpriteSheetPage>,
+) -> Vec<SpriteSheetPage> {
+    return pages.into_iter().map(|page| {
+        let image_path = sprite_sheet::resolve_image_path(related_path, page.image_path.as_deref());
+
+        // The related data files are relative to the data file, so their images are too
+        let image_path = image_path.strip_prefix(path.parent().unwrap_or(Path::new(""))).unwrap_or(&image_path);
+
+        return SpriteSheetPage {
+            image_path: Some(image_path.to_string_lossy().to_string()),
+            frames: page.frames,
+        };
+    }).collect();
+}
+
+// Checks whether the frames of the Json content are stored in a hash, in an array or in several textures
+fn detect_layout(
+    path: &Path,
+    content: &str,
+) -> Result<Layout, SpriteSheetError> {
+    #[derive(Deserialize)]
+    struct LayoutData {
+        #[serde(default)]
+        frames: serde_json::Value,
+        #[serde(default)]
+        textures: Option<serde_json::Value>,
+    }
+
+    let layout: LayoutData = serde_json::from_str(content.trim_start_matches('\u{FEFF}'))
+        .map_err(|error| SpriteSheetError::from_json(path, error))?;
+
+    if layout.textures.is_some() {
+        return Ok(Layout::MultiAtlas);
+    }
+
+    if layout.frames.is_array() {
+        return Ok(Layout::Array);
+    }
+
+    return Ok(Layout::Hash);
+}
+
+/// Asset loader for the Json, Json Array, Phaser 3 and Edge Animate data formats.
+///
+/// Loads `.json` and `.eas` files as a [`SpriteSheet`](crate::SpriteSheet). Whether a `.json`
+/// file uses the Json, the Json Array or the Phaser 3 layout is detected from its `frames` and
+/// `textures`. The image is loaded as a dependency using `meta.image`, or the `image` of every
+/// texture of a Phaser 3 multi-atlas, relative to the data file. If it is missing, a `.png`
+/// with the same name as the data file is used instead. The data files listed in
+/// `meta.related_multi_packs` by a TexturePacker multipack are added as further pages. The
+/// frame tags and slices of Aseprite exports are added to the `SpriteSheet`.
+///
+/// The texture atlas of the first page is available as the labeled asset `atlas`, the ones of
+/// the following pages as `atlas1`, `atlas2`, ...
+///
+/// The `Animation.json` of an Adobe Animate texture atlas is loaded as an
+/// [`AnimationLibrary`](crate::AnimationLibrary) instead, see
+/// [`AnimationLibrary::from_animate_atlas`](crate::AnimationLibrary::from_animate_atlas).
+#[derive(Debug, Default)]
+pub struct JsonLoader;
+
+impl AssetLoader for JsonLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let content = std::str::from_utf8(bytes)?;
+
+            if animate_atlas::is_animate_atlas(content) {
+                return animate_atlas::set_animation_library_asset(load_context, content).await;
             }
-        );
+
+            let mut pages = parse_layout(load_context.path(), content)?;
+            pages.extend(load_multi_packs(load_context, load_context.path(), content).await?);
+
+            let (tags, slices) = aseprite::parse_meta(load_context.path(), content)?;
+
+            sprite_sheet::set_sprite_sheet_asset(load_context, pages, tags, slices)?;
+
+            return Ok(());
+        })
     }
 
-    return Some(
-        AnimatedSpriteBundle {
-            sprite_sheet_bundle: SpriteSheetBundle {
-                texture_atlas: texture_atlas_handle,
-                ..default()
-            },
-            animated_sprite: animated_sprite,
-        }
-    );
-}
\ No newline at end of file
+    fn extensions(&self) -> &[&str] {
+        return &["json", "eas"];
+    }
+}
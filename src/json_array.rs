@@ -1,109 +1,64 @@
 // Import necessary modules and crates
+use std::path::Path;
+
 use bevy::prelude::*;
 
 use serde::Deserialize;
-use serde_json;
-
-use crate::AnimatedSpriteBundle;
-use crate::AnimatedSprite;
-use crate::FrameOffset;
 
-#[derive(Debug, Default, Deserialize)]
-struct Frame {
-    x: u32,
-    y: u32,
-    w: u32,
-    h: u32,
-}
+use crate::AnimationLibrary;
+use crate::SpriteSheetError;
+use crate::json::{FrameData, Meta};
+use crate::sprite_sheet::{self, SpriteSheetFrame, SpriteSheetPage};
 
 #[derive(Debug, Default, Deserialize)]
-struct SpriteSourceSize {
-    x: u32,
-    y: u32,
-    // we dont need these
-    // w: u32,
-    // h: u32,
-}
-
-#[derive(Debug, Default, Deserialize)]
-struct FrameData {
-    filename: String,
-    frame: Frame,
-    rotated: bool,
-    #[serde(rename = "spriteSourceSize")]
-    sprite_source_size: SpriteSourceSize,
+pub(crate) struct NamedFrameData {
+    pub(crate) filename: String,
+    #[serde(flatten)]
+    pub(crate) data: FrameData,
 }
 
 #[derive(Debug, Default, Deserialize)]
 struct Frames {
-    frames: Vec<FrameData>,
+    frames: Vec<NamedFrameData>,
+    #[serde(default)]
+    meta: Meta,
 }
 
-pub fn create_animated_sprite_bundle(
-    path: &str,
-    is_edge_animate: bool,
-    texture_atlases: &mut Assets<TextureAtlas>,
-    asset_server: &AssetServer,
-) -> Option<AnimatedSpriteBundle> {
-    // Load Json content from file
-    let content = std::fs::read_to_string(format!("assets/{path}{}", if is_edge_animate {".eas"} else {".json"})).ok()?;
-
+// Deserializes the Json Array content and converts the frames into sprite sheet frames
+pub(crate) fn parse(
+    path: &Path,
+    content: &str,
+) -> Result<(Option<String>, Vec<SpriteSheetFrame>), SpriteSheetError> {
     // Remove the BOM if present (UTF-8 BOM is 0xEF, 0xBB, 0xBF)
-    let content = content.trim_start_matches('\u{FEFF}').to_string();
+    let content = content.trim_start_matches('\u{FEFF}');
 
     // Deserialize Json data
-    let json_data: Frames = serde_json::from_str(&content).ok()?;
+    let json_data: Frames = serde_json::from_str(content).map_err(|error| SpriteSheetError::from_json(path, error))?;
 
-    // Load texture atlas and prepare sprite sheet bundle
-    let texture_atlas_handle = texture_atlases.add(
-        TextureAtlas::new_empty(
-            asset_server.load(format!("{path}.png")), 
-            Vec2::default()
-        )
-    );
+    let frames = json_data.frames.iter().map(|frame| frame.data.to_sprite_sheet_frame(&frame.filename)).collect();
 
-    let texture_atlas = texture_atlases.get_mut(&texture_atlas_handle)?;
+    return Ok((json_data.meta.image, frames));
+}
 
-    // Prepare animated sprite data
-    let mut animated_sprite = AnimatedSprite::default();
+pub fn create_animation_library(
+    path: &str,
+    is_edge_animate: bool,
+    texture_atlases: &mut Assets<TextureAtlas>,
+    asset_server: &AssetServer,
+) -> Result<AnimationLibrary, SpriteSheetError> {
+    let data_path = format!("{path}{}", if is_edge_animate {".eas"} else {".json"});
 
-    // Add frames to the texture atlas and animated sprite data
-    for frame in json_data.frames.iter() {
-        // Add texture to atlas
-        let index = texture_atlas.add_texture(
-            Rect::new(
-                frame.frame.x as f32,
-                frame.frame.y as f32,
-                (frame.frame.x + frame.frame.w) as f32,
-                (frame.frame.y + frame.frame.h) as f32,
-            )
-        );
+    let data_path = Path::new(&data_path);
 
-        // Insert texture index into frames and set frame offset
-        animated_sprite.frames.insert(
-            frame.filename.clone(),
-            index
-        );
+    // Load Json content from file
+    let content = sprite_sheet::read_data_file(asset_server, data_path)?;
 
-        animated_sprite.frame_offsets.insert(
-            index,
-            FrameOffset {
-                position_offset: Vec2::new(
-                    frame.sprite_source_size.x as f32 * -0.5, // negative because for some reason
-                    frame.sprite_source_size.y as f32 * -0.5, // the json has the inverted sign
-                ),
-                rotation_offset: if frame.rotated {std::f32::consts::PI * 0.5} else {0f32},
-            }
-        );
-    }
+    let pages = SpriteSheetPage::single(parse(data_path, &content)?);
 
-    return Some(
-        AnimatedSpriteBundle {
-            sprite_sheet_bundle: SpriteSheetBundle {
-                texture_atlas: texture_atlas_handle,
-                ..default()
-            },
-            animated_sprite: animated_sprite,
-        }
+    return sprite_sheet::create_animation_library(
+        data_path,
+        pages,
+        texture_atlases,
+        asset_server,
     );
-}
\ No newline at end of file
+}
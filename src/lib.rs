@@ -1,74 +1,138 @@
+#![allow(clippy::needless_return)]
+
 // Import necessary modules and crates
-use std::collections::HashMap;
+use std::collections::{HashSet, VecDeque};
 
 use bevy::prelude::*;
+use bevy::asset::HandleId;
+use bevy::sprite::Anchor;
 
 mod sparrow;
 mod json;
 mod json_array;
+mod sprite_sheet;
+mod error;
+mod animation_library;
+mod animation_definition;
+mod events;
+mod frame_events;
+mod plugin;
+mod state_machine;
+mod reload;
+mod animate_atlas;
+mod aseprite;
+mod aseprite_file;
+mod phaser;
+mod cocos2d;
+mod libgdx;
+mod rotated_frames;
+mod composed_frames;
 
-/// Struct containing animation data.
-///
-/// This struct represents the properties and settings of an animation that can be played
-/// on an `AnimatedSprite`. It includes information such as the animation's name, frames
-/// per second (fps), loop status, offset, current frame index, and a timer for controlling
-/// animation timing.
-///
-/// # Fields
-///
-/// - `name`: The name of the animation.
-/// - `fps`: The frames per second at which the animation should play.
-/// - `looped`: A boolean indicating whether the animation should loop when it reaches
-///   its end.
-/// - `offset`: A 2D vector representing the offset to apply to the animation.
-/// - `indices`: A vector of frame indices that make up the animation.
-/// - `current_index`: The index of the current frame within the animation.
-/// - `timer`: A timer used to control the animation's frame switching.
-#[derive(Debug, Default, Clone)]
-pub struct AnimationData {
-    /// The name of the animation.
-    pub name: String,
-    /// The frames per second at which the animation should play.
-    pub fps: u8,
-    /// Indicates whether the animation should loop when it reaches its end.
-    pub looped: bool,
-    /// A 2D vector representing the offset to apply to the animation.
-    pub offset: Vec2,
-    /// A vector of frame indices that make up the animation.
-    pub indices: Vec<usize>,
-    /// The index of the current frame within the animation.
-    pub current_index: usize,
-    /// A timer used to control the animation's frame switching.
-    pub timer: Timer,
+pub use animation_library::{AnimationData, AnimationLibrary, LoopMode};
+pub use animation_definition::AnimationLibraryLoader;
+pub use aseprite::{FrameTag, Slice, SliceKey, TagDirection};
+pub use aseprite_file::AsepriteLoader;
+pub use events::{
+    AnimationEvent, AnimationEventWriters, AnimationFinished, AnimationLooped, AnimationReloaded, AnimationStarted,
+    FrameChanged,
+};
+pub use frame_events::{FrameEvent, FrameEvents, FrameEventsLoader};
+pub use plugin::{SpriteSheetAnimationPlugin, SpriteSheetAnimationSystem};
+pub use state_machine::{
+    AnimationCondition, AnimationGraph, AnimationGraphLoader, AnimationState, AnimationStateMachine,
+    AnimationTransition,
+};
+pub use error::SpriteSheetError;
+pub use sparrow::SparrowLoader;
+pub use json::JsonLoader;
+pub use cocos2d::Cocos2dLoader;
+pub use libgdx::LibGdxLoader;
+pub use sprite_sheet::{SpriteSheet, SpriteSheetFrame};
+
+// Position requested by the seek methods of AnimatedSprite, applied by update_animations
+#[derive(Debug, Clone, Copy)]
+enum Seek {
+    Frame(usize),
+    Time(f32),
+    NormalizedTime(f32),
 }
 
-#[derive(Debug, Default)]
-struct FrameOffset {
-    position_offset: Vec2,
-    rotation_offset: f32,
+// Animation waiting in the queue of an AnimatedSprite
+#[derive(Debug, Clone)]
+struct QueuedAnimation {
+    name: String,
+    times: Option<u32>,
 }
 
 /// Struct representing an animated sprite.
 ///
-/// The `AnimatedSprite` struct contains information about animations and provides methods
-/// to manage and play animations on a sprite. It allows you to add animations, play specific
-/// animations, pause, resume, and retrieve information about the current animation.
-/// 
+/// The `AnimatedSprite` struct contains the playback state of an animated sprite and provides
+/// methods to play, queue, pause, resume, seek, and retrieve information about the current animation.
+/// The animations themselves are stored in the `AnimationLibrary` referenced by `library`,
+/// which is shared by every entity using the same sprite sheet.
+///
+/// Frame and animation offsets are applied through the `anchor` of the `TextureAtlasSprite`,
+/// so the `Transform` of the entity is never modified by the animation.
+///
 /// # Fields
 ///
+/// - `library`: The handle to the `AnimationLibrary` containing the animations.
+/// - `anchor`: The anchor of the sprite relative to the untrimmed frame.
+/// - `use_frame_pivots`: A boolean indicating whether the pivots of the frames are used instead of `anchor`.
+/// - `speed`: The playback speed multiplier, negative speeds play the animation backwards.
 /// - `animation_is_finished`: A boolean indicating whether the current animation has finished playing.
 /// - `animation_is_paused`: A boolean indicating whether the current animation is paused.
-#[derive(Debug, Default, Component)]
+#[derive(Debug, Component)]
 pub struct AnimatedSprite {
+    /// The handle to the `AnimationLibrary` containing the animations.
+    pub library: Handle<AnimationLibrary>,
+    /// The anchor of the sprite relative to the untrimmed frame, before the animation offset
+    /// is applied. Trimmed frames are moved so their pixels stay where they were before trimming.
+    pub anchor: Anchor,
+    /// Indicates whether the pivots of the frames (`pivotX`/`pivotY` in Sparrow v2, `pivot` in
+    /// Json) are used instead of `anchor` for the frames that have one. Enabled by default.
+    pub use_frame_pivots: bool,
+    /// The playback speed multiplier, e.g. `0.5` for half speed. Negative speeds play the
+    /// animation backwards, starting at its last frame.
+    pub speed: f32,
     /// Indicates whether the animation has finished playing.
     pub animation_is_finished: bool,
     /// Indicates whether the animation is currently paused.
     pub animation_is_paused: bool,
-    
-    animations: Vec<AnimationData>,
-    frames: HashMap<String, usize>,
-    frame_offsets: Vec<FrameOffset>,
+
+    current_animation: Option<String>,
     current_animation_index: Option<usize>,
+    current_times: Option<u32>,
+    current_index: usize,
+    elapsed: f32,
+    passes: u32,
+    ping_pong_reversed: bool,
+    is_hidden: bool,
+    seek: Option<Seek>,
+    queue: VecDeque<QueuedAnimation>,
+}
+
+impl Default for AnimatedSprite {
+    fn default() -> Self {
+        return AnimatedSprite {
+            library: Handle::default(),
+            anchor: Anchor::default(),
+            use_frame_pivots: true,
+            speed: 1f32,
+            animation_is_finished: false,
+            animation_is_paused: false,
+            current_animation: None,
+            current_animation_index: None,
+            current_times: None,
+            current_index: 0,
+            elapsed: 0f32,
+            passes: 0,
+            ping_pong_reversed: false,
+            is_hidden: false,
+            seek: None,
+            queue: VecDeque::new(),
+        };
+    }
 }
 
 /// Bundle for creating an AnimatedSprite
@@ -79,270 +143,334 @@ pub struct AnimatedSpriteBundle {
 }
 
 impl AnimatedSpriteBundle {
+    /// Creates an `AnimatedSpriteBundle` using the animations of an `AnimationLibrary`.
+    ///
+    /// The texture atlas of the library is set once the first animation is played.
+    ///
+    /// # Parameters
+    ///
+    /// - `library`: The handle to the `AnimationLibrary` containing the animations.
+    pub fn new(
+        library: Handle<AnimationLibrary>,
+    ) -> Self {
+        return AnimatedSpriteBundle {
+            sprite_sheet_bundle: SpriteSheetBundle::default(),
+            animated_sprite: AnimatedSprite {
+                library,
+                ..default()
+            },
+        };
+    }
+
+    // Adds the library and creates a bundle using it, showing the texture atlas of its first page
+    fn from_library(
+        library: AnimationLibrary,
+        libraries: &mut Assets<AnimationLibrary>,
+    ) -> Self {
+        let texture_atlas = library.texture_atlas.clone();
+
+        let mut bundle = AnimatedSpriteBundle::new(libraries.add(library));
+        bundle.sprite_sheet_bundle.texture_atlas = texture_atlas;
+
+        return bundle;
+    }
+
+    /// Creates an `AnimatedSpriteBundle` from a loaded `SpriteSheet` asset.
+    ///
+    /// The library created by [`AnimationLibrary::from_sprite_sheet`] is added to `libraries`,
+    /// animations can be added to it through `libraries.get_mut(&bundle.animated_sprite.library)`.
+    ///
+    /// # Parameters
+    ///
+    /// - `sprite_sheet`: A reference to the loaded `SpriteSheet`.
+    /// - `libraries`: A mutable reference to the `Assets<AnimationLibrary>` resource.
+    ///
+    /// # Returns
+    ///
+    /// The animated sprite bundle using the texture atlas of the sprite sheet.
+    pub fn from_sprite_sheet(
+        sprite_sheet: &SpriteSheet,
+        libraries: &mut Assets<AnimationLibrary>,
+    ) -> Self {
+        return AnimatedSpriteBundle::from_library(AnimationLibrary::from_sprite_sheet(sprite_sheet), libraries);
+    }
+
     /// Creates an `AnimatedSpriteBundle` from a Sparrow v1 or Sparrow v2 data format.
     ///
+    /// The library created by [`AnimationLibrary::from_sparrow`] is added to `libraries`,
+    /// animations can be added to it through `libraries.get_mut(&bundle.animated_sprite.library)`.
+    ///
     /// # Parameters
     ///
     /// - `path`: The path to the sprite sheet and data file.
     /// - `texture_atlases`: A mutable reference to the `Assets<TextureAtlas>` resource.
     /// - `asset_server`: A reference to the `AssetServer`.
+    /// - `libraries`: A mutable reference to the `Assets<AnimationLibrary>` resource.
     ///
     /// # Returns
     ///
-    /// An `Option<Self>` containing the animated sprite bundle if successful, or `None` if an error occurs.
+    /// A `Result` containing the animated sprite bundle if successful, or a `SpriteSheetError`
+    /// describing which file couldn't be loaded and why.
     pub fn from_sparrow(
         path: &str,
         texture_atlases: &mut Assets<TextureAtlas>,
         asset_server: &AssetServer,
-    ) -> Option<Self> {
-        return sparrow::create_animated_sprite_bundle(path, texture_atlases, asset_server);
+        libraries: &mut Assets<AnimationLibrary>,
+    ) -> Result<Self, SpriteSheetError> {
+        let library = AnimationLibrary::from_sparrow(path, texture_atlases, asset_server)?;
+
+        return Ok(AnimatedSpriteBundle::from_library(library, libraries));
     }
 
     /// Creates an `AnimatedSpriteBundle` from a Starling data format.
     ///
+    /// The library created by [`AnimationLibrary::from_starling`] is added to `libraries`,
+    /// animations can be added to it through `libraries.get_mut(&bundle.animated_sprite.library)`.
+    ///
     /// # Parameters
     ///
     /// - `path`: The path to the sprite sheet and data file.
     /// - `texture_atlases`: A mutable reference to the `Assets<TextureAtlas>` resource.
     /// - `asset_server`: A reference to the `AssetServer`.
+    /// - `libraries`: A mutable reference to the `Assets<AnimationLibrary>` resource.
     ///
     /// # Returns
     ///
-    /// An `Option<Self>` containing the animated sprite bundle if successful, or `None` if an error occurs.
+    /// A `Result` containing the animated sprite bundle if successful, or a `SpriteSheetError`
+    /// describing which file couldn't be loaded and why.
     pub fn from_starling(
         path: &str,
         texture_atlases: &mut Assets<TextureAtlas>,
         asset_server: &AssetServer,
-    ) -> Option<Self> {
-        return sparrow::create_animated_sprite_bundle(path, texture_atlases, asset_server);
+        libraries: &mut Assets<AnimationLibrary>,
+    ) -> Result<Self, SpriteSheetError> {
+        let library = AnimationLibrary::from_starling(path, texture_atlases, asset_server)?;
+
+        return Ok(AnimatedSpriteBundle::from_library(library, libraries));
     }
 
     /// Creates an `AnimatedSpriteBundle` from a JSON data format.
     ///
+    /// The library created by [`AnimationLibrary::from_json`] is added to `libraries`,
+    /// animations can be added to it through `libraries.get_mut(&bundle.animated_sprite.library)`.
+    ///
     /// # Parameters
     ///
     /// - `path`: The path to the sprite sheet and data file.
     /// - `texture_atlases`: A mutable reference to the `Assets<TextureAtlas>` resource.
     /// - `asset_server`: A reference to the `AssetServer`.
+    /// - `libraries`: A mutable reference to the `Assets<AnimationLibrary>` resource.
     ///
     /// # Returns
     ///
-    /// An `Option<Self>` containing the animated sprite bundle if successful, or `None` if an error occurs.
+    /// A `Result` containing the animated sprite bundle if successful, or a `SpriteSheetError`
+    /// describing which file couldn't be loaded and why.
     pub fn from_json(
         path: &str,
         texture_atlases: &mut Assets<TextureAtlas>,
         asset_server: &AssetServer,
-    ) -> Option<Self> {
-        return json::create_animated_sprite_bundle(path, texture_atlases, asset_server);
+        libraries: &mut Assets<AnimationLibrary>,
+    ) -> Result<Self, SpriteSheetError> {
+        let library = AnimationLibrary::from_json(path, texture_atlases, asset_server)?;
+
+        return Ok(AnimatedSpriteBundle::from_library(library, libraries));
     }
 
     /// Creates an `AnimatedSpriteBundle` from a JSON Array data format.
     ///
+    /// The library created by [`AnimationLibrary::from_json_array`] is added to `libraries`,
+    /// animations can be added to it through `libraries.get_mut(&bundle.animated_sprite.library)`.
+    ///
     /// # Parameters
     ///
     /// - `path`: The path to the sprite sheet and data file.
     /// - `texture_atlases`: A mutable reference to the `Assets<TextureAtlas>` resource.
     /// - `asset_server`: A reference to the `AssetServer`.
+    /// - `libraries`: A mutable reference to the `Assets<AnimationLibrary>` resource.
     ///
     /// # Returns
     ///
-    /// An `Option<Self>` containing the animated sprite bundle if successful, or `None` if an error occurs.
+    /// A `Result` containing the animated sprite bundle if successful, or a `SpriteSheetError`
+    /// describing which file couldn't be loaded and why.
     pub fn from_json_array(
         path: &str,
         texture_atlases: &mut Assets<TextureAtlas>,
         asset_server: &AssetServer,
-    ) -> Option<Self> {
-        return json_array::create_animated_sprite_bundle(path, false, texture_atlases, asset_server);
+        libraries: &mut Assets<AnimationLibrary>,
+    ) -> Result<Self, SpriteSheetError> {
+        let library = AnimationLibrary::from_json_array(path, texture_atlases, asset_server)?;
+
+        return Ok(AnimatedSpriteBundle::from_library(library, libraries));
     }
 
     /// Creates an `AnimatedSpriteBundle` from an Edge Animate data format.
     ///
+    /// The library created by [`AnimationLibrary::from_edge_animate`] is added to `libraries`,
+    /// animations can be added to it through `libraries.get_mut(&bundle.animated_sprite.library)`.
+    ///
     /// # Parameters
     ///
     /// - `path`: The path to the sprite sheet and data file.
     /// - `texture_atlases`: A mutable reference to the `Assets<TextureAtlas>` resource.
     /// - `asset_server`: A reference to the `AssetServer`.
+    /// - `libraries`: A mutable reference to the `Assets<AnimationLibrary>` resource.
     ///
     /// # Returns
     ///
-    /// An `Option<Self>` containing the animated sprite bundle if successful, or `None` if an error occurs.
+    /// A `Result` containing the animated sprite bundle if successful, or a `SpriteSheetError`
+    /// describing which file couldn't be loaded and why.
     pub fn from_edge_animate(
         path: &str,
         texture_atlases: &mut Assets<TextureAtlas>,
         asset_server: &AssetServer,
-    ) -> Option<Self> {
-        return json_array::create_animated_sprite_bundle(path, true, texture_atlases, asset_server);
+        libraries: &mut Assets<AnimationLibrary>,
+    ) -> Result<Self, SpriteSheetError> {
+        let library = AnimationLibrary::from_edge_animate(path, texture_atlases, asset_server)?;
+
+        return Ok(AnimatedSpriteBundle::from_library(library, libraries));
     }
 }
 
 // Implementation of methods for the AnimatedSprite struct
 impl AnimatedSprite {
-    /// Adds a new animation using specific frames.
+    /// Plays a specific animation on the `AnimatedSprite`.
     ///
-    /// This method adds an animation to the `AnimatedSprite` using the provided frames,
-    /// frames-per-second (fps), looped status, and offset.
+    /// The animation is looked up in the `AnimationLibrary` and applied to the sprite the next
+    /// time `update_animations` runs, which also waits for the library to be loaded.
+    /// Playing an animation clears the queue.
     ///
     /// # Parameters
     ///
-    /// - `animation_name`: Name of the animation to be added.
-    /// - `frames`: Vector of frame names that compose the animation.
-    /// - `fps`: Frames per second of the animation.
-    /// - `looped`: Indicates whether the animation should loop.
-    /// - `offset`: Offset applied to the animation.
-    pub fn add_animation_by_frames(
+    /// - `animation_name`: Name of the animation to be played.
+    /// - `forced`: Forces the animation to play even if it's the current animation.
+    pub fn play_animation(
         &mut self,
         animation_name: &str,
-        frames: Vec<String>,
-        fps: u8,
-        looped: bool,
-        offset: Vec2,
+        forced: bool,
     ) {
-        if frames.len() == 0 {
-            println!("\x1b[38;5;196mAnimation ({animation_name}) wasn't created because it had 0 frames\x1b[0;0;0m");
+        if !forced && self.current_animation.as_deref() == Some(animation_name) {
             return;
         }
 
-        // Check if animation already exists with this name and remove it
-        if let Some(index) = self.animations.iter().position(|animation| animation.name == animation_name) {
-            self.animations.remove(index);
-        }
-
-        // Add the new animation
-        self.animations.push(
-            AnimationData {
-                name: animation_name.to_string(),
-                fps: fps,
-                looped: looped,
-                offset: offset,
-                indices: frames.iter().filter_map(|frame| self.frames.get(frame)).copied().collect(),
-                current_index: 0,
-                timer: Timer::from_seconds(1f32 / (fps as f32), TimerMode::Once)
-            }
-        );
+        self.queue.clear();
+        self.request_animation(animation_name, None);
     }
 
-    /// Adds a new animation using frames with a specific prefix.
+    /// Queues an animation to be played after the current and already queued animations.
     ///
-    /// This method collects frames with the specified prefix, sorts them, and then
-    /// adds the animation to the `AnimatedSprite`.
+    /// The next animation starts on the frame the previous one finishes, carrying over the
+    /// remaining time. If no animation is playing or it has already finished, the animation
+    /// starts the next time `update_animations` runs.
     ///
     /// # Parameters
     ///
-    /// - `animation_name`: Name of the animation to be added.
-    /// - `prefix`: Prefix used to identify frames for the animation.
-    /// - `fps`: Frames per second of the animation.
-    /// - `looped`: Indicates whether the animation should loop.
-    /// - `offset`: Offset applied to the animation.
-    pub fn add_animation_by_prefix(
+    /// - `animation_name`: Name of the animation to be queued.
+    /// - `times`: How many times the animation is played before moving on, overriding its
+    ///   loop mode. Ping-pong animations count back and forth cycles. Without it, animations
+    ///   that loop forever never finish, so the animations queued after them never play.
+    pub fn queue_animation(
         &mut self,
         animation_name: &str,
-        prefix: &str,
-        fps: u8,
-        looped: bool,
-        offset: Vec2,
+        times: Option<u32>,
     ) {
-        // Collect frames with the specified prefix and sort them
-        let mut frames: Vec<String> = self.frames.keys().filter(|frame| frame.starts_with(prefix)).cloned().collect();
-        frames.sort();
-        // Add the animation using the collected frames
-        self.add_animation_by_frames(
-            animation_name,
-            frames,
-            fps,
-            looped,
-            offset,
+        self.queue.push_back(
+            QueuedAnimation {
+                name: animation_name.to_string(),
+                times,
+            }
         );
     }
 
-    /// Plays a specific animation on the `AnimatedSprite`.
+    /// Removes every queued animation, the current animation keeps playing.
+    pub fn clear_queue(
+        &mut self
+    ) {
+        self.queue.clear();
+    }
+
+    /// Retrieves the names of the queued animations, in the order they will be played.
+    pub fn queued_animations(
+        &self
+    ) -> impl Iterator<Item = &str> {
+        return self.queue.iter().map(|queued| queued.name.as_str());
+    }
+
+    // Resets the animation status, the animation index is resolved by update_animations
+    fn request_animation(
+        &mut self,
+        animation_name: &str,
+        times: Option<u32>,
+    ) {
+        self.animation_is_finished = false;
+        self.animation_is_paused = false;
+        self.current_animation = Some(animation_name.to_string());
+        self.current_animation_index = None;
+        self.current_times = times;
+        self.current_index = 0;
+        self.seek = None;
+    }
+
+    /// Plays a specific animation on the `AnimatedSprite`, starting at a point in time.
     ///
-    /// This method searches for the animation by name and plays it on the provided sprite
-    /// and transform.
+    /// The animation is always restarted, e.g. to desynchronize identical sprites by starting
+    /// them at random times.
     ///
     /// # Parameters
     ///
     /// - `animation_name`: Name of the animation to be played.
-    /// - `forced`: Forces the animation to play even if it's the current animation.
-    /// - `sprite`: Reference to the sprite to which the animation is applied.
-    /// - `transform`: Reference to the transform of the sprite.
-    pub fn play_animation(
+    /// - `time`: The time since the start of the animation in seconds.
+    pub fn play_animation_at(
         &mut self,
         animation_name: &str,
-        forced: bool,
-        sprite: &mut TextureAtlasSprite,
-        transform: &mut Transform,
+        time: f32,
     ) {
-        // Search for the animation index
-        let mut anim_index: Option<usize> = None;
-        for (index, animation) in self.animations.iter().enumerate() {
-            if animation.name == animation_name {
-                anim_index = Some(index);
-                break;
-            }
-        }
-
-        // Check if animation exists, else return
-        if anim_index.is_none() {
-            println!("\x1b[38;5;196mAnimation ({animation_name}) doesn't exist\x1b[0;0;0m");
-            return;
-        }
-
-        // Handle the current animation
-        if let Some(current_animation_index) = self.current_animation_index {
-            if let Some(current_animation) = self.animations.get_mut(current_animation_index) {
-                if !forced && current_animation.name == animation_name {
-                    return;
-                }
-
-                current_animation.timer.reset();
-
-                current_animation.current_index = 0;
-
-                // Remove frame offset
-                transform.translation -= Vec3::new(
-                    self.frame_offsets[sprite.index].position_offset.x,
-                    self.frame_offsets[sprite.index].position_offset.y,
-                    0f32,
-                ) * transform.scale;
-
-                // Remove frame rotation
-                transform.rotate_local_z(-self.frame_offsets[sprite.index].rotation_offset as f32);
-
-                // Remove animation offset
-                transform.translation -= Vec3::new(
-                    current_animation.offset.x,
-                    current_animation.offset.y,
-                    0f32,
-                ) * transform.scale;
-            }
-        }
-
-        // Reset animation status and set the new animation index
-        self.animation_is_finished = false;
-        self.animation_is_paused = false;
-        self.current_animation_index = anim_index;
-
-        let animation = &mut self.animations[self.current_animation_index.unwrap()];
-
-        animation.current_index = 0;
-        sprite.index = animation.indices[animation.current_index];
+        self.play_animation(animation_name, true);
+        self.seek = Some(Seek::Time(time));
+    }
 
-        // Set frame offset
-        transform.translation += Vec3::new(
-            self.frame_offsets[sprite.index].position_offset.x,
-            self.frame_offsets[sprite.index].position_offset.y,
-            0f32,
-        ) * transform.scale;
+    /// Jumps to a frame of the current animation.
+    ///
+    /// The frame is applied the next time `update_animations` runs. A finished animation
+    /// continues playing from the frame unless it is paused.
+    ///
+    /// # Parameters
+    ///
+    /// - `frame`: The index of the frame within the animation, clamped to its last frame.
+    pub fn set_frame(
+        &mut self,
+        frame: usize,
+    ) {
+        self.seek = Some(Seek::Frame(frame));
+    }
 
-        // Set frame rotation
-        transform.rotate_local_z(self.frame_offsets[sprite.index].rotation_offset as f32);
+    /// Jumps to a point in time of the current animation.
+    ///
+    /// The time is applied the next time `update_animations` runs. A finished animation
+    /// continues playing from the time unless it is paused.
+    ///
+    /// # Parameters
+    ///
+    /// - `time`: The time since the start of the animation in seconds, clamped to its duration.
+    pub fn set_time(
+        &mut self,
+        time: f32,
+    ) {
+        self.seek = Some(Seek::Time(time));
+    }
 
-        // Set animation offset
-        transform.translation += Vec3::new(
-            animation.offset.x,
-            animation.offset.y,
-            0f32,
-        ) * transform.scale;
+    /// Jumps to a point in time of the current animation relative to its duration.
+    ///
+    /// The time is applied the next time `update_animations` runs. A finished animation
+    /// continues playing from the time unless it is paused.
+    ///
+    /// # Parameters
+    ///
+    /// - `normalized_time`: The time between `0.0` (start) and `1.0` (end) of the animation.
+    pub fn set_normalized_time(
+        &mut self,
+        normalized_time: f32,
+    ) {
+        self.seek = Some(Seek::NormalizedTime(normalized_time));
     }
 
     /// Pauses the current animation.
@@ -353,7 +481,7 @@ impl AnimatedSprite {
     ) {
         self.animation_is_paused = true;
     }
-    
+
     /// Resumes the current animation.
     ///
     /// This method resumes a paused animation.
@@ -362,137 +490,558 @@ impl AnimatedSprite {
     ) {
         self.animation_is_paused = false;
     }
-    
-    /// Retrieves information about the current animation.
-    ///
-    /// This method returns an instance of `AnimationData` containing information
-    /// about the currently playing animation.
+
+    /// Retrieves the name of the current animation.
     ///
     /// # Returns
     ///
-    /// An `AnimationData` object representing the current animation.
+    /// The name of the current animation, or `None` if no animation is playing.
     pub fn current_animation(
         &self
-    ) -> AnimationData {
-        if let Some(index) = self.current_animation_index {
-            return self.animations[index].clone();
+    ) -> Option<&str> {
+        return self.current_animation.as_deref();
+    }
+
+    /// Retrieves the index of the current frame within the current animation.
+    pub fn current_frame(
+        &self
+    ) -> usize {
+        return self.current_index;
+    }
+
+    /// Retrieves how far the current pass of the current animation has played.
+    ///
+    /// # Returns
+    ///
+    /// The time between `0.0` (start) and `1.0` (end) of the pass, in the direction of playback.
+    pub(crate) fn normalized_time(
+        &self,
+        library: &AnimationLibrary,
+    ) -> f32 {
+        let Some(index) = self.current_animation_index else {
+            return 0f32;
+        };
+
+        if self.animation_is_finished {
+            return 1f32;
+        }
+
+        let animation = library.animation_at(index);
+        let duration = animation.duration();
+
+        if duration.is_nan() || duration <= 0f32 {
+            return 0f32;
+        }
+
+        let reversed = (self.speed < 0f32) != self.ping_pong_reversed;
+
+        // Sum the durations of the frames that were already played in this pass
+        let played: f32 = if reversed {
+            (self.current_index + 1..animation.indices.len()).map(|frame| animation.frame_duration(frame)).sum()
         } else {
-            println!("No Animation Playing");
-            return AnimationData::default();
+            (0..self.current_index).map(|frame| animation.frame_duration(frame)).sum()
+        };
+
+        return ((played + self.elapsed) / duration).min(1f32);
+    }
+
+    /// Retrieves how far the current animation has played since it started, counting its passes.
+    ///
+    /// # Returns
+    ///
+    /// The number of completed passes plus the normalized time of the current pass, e.g. `1.0`
+    /// at the end of the first pass and `2.5` in the middle of the third one.
+    pub(crate) fn played_passes(
+        &self,
+        library: &AnimationLibrary,
+    ) -> f32 {
+        if self.current_animation_index.is_none() {
+            return 0f32;
+        }
+
+        // The pass that finished the animation is already counted
+        if self.animation_is_finished {
+            return self.passes as f32;
         }
+
+        return self.passes as f32 + self.normalized_time(library);
     }
 
-    /// Moves to the next frame of the current animation.
+    /// Checks whether the current animation still matches the library and texture atlas.
+    ///
+    /// # Returns
     ///
-    /// This method advances the animation to the next frame and updates the sprite and transform.
+    /// `false` if the library was changed since the animation started.
+    fn is_up_to_date(
+        &self,
+        library: &AnimationLibrary,
+        texture_atlas: &Handle<TextureAtlas>,
+    ) -> bool {
+        let Some(index) = self.current_animation_index else {
+            return true;
+        };
+
+        return library.animations().get(index).is_some_and(|animation| {
+            Some(animation.name.as_str()) == self.current_animation.as_deref()
+                && self.current_index < animation.indices.len()
+                && *texture_atlas == *library.frame_atlas(animation.indices[self.current_index])
+        });
+    }
+
+    /// Looks up the current animation again after the library was modified or reloaded.
+    ///
+    /// The current animation keeps playing if it still exists, frames that don't exist
+    /// anymore are clamped to its last frame. Animations without frames are stopped.
     ///
     /// # Parameters
     ///
+    /// - `library`: Reference to the library containing the animations.
+    fn reload(
+        &mut self,
+        library: &AnimationLibrary,
+    ) {
+        // Animations that haven't started yet are looked up when they start
+        if self.current_animation_index.is_none() {
+            return;
+        }
+
+        let Some(animation_name) = self.current_animation.as_deref() else {
+            return;
+        };
+
+        let Some(index) = library.animation_index(animation_name) else {
+            println!("\x1b[38;5;196mAnimation ({animation_name}) doesn't exist anymore\x1b[0;0;0m");
+            self.current_animation = None;
+            self.current_animation_index = None;
+            return;
+        };
+
+        let animation = library.animation_at(index);
+
+        // The frames can be removed through animation_mut
+        if animation.indices.is_empty() {
+            println!("\x1b[38;5;196mAnimation ({animation_name}) has no frames anymore\x1b[0;0;0m");
+            self.current_animation = None;
+            self.current_animation_index = None;
+            return;
+        }
+
+        self.current_animation_index = Some(index);
+        self.current_index = self.current_index.min(animation.indices.len() - 1);
+    }
+
+    /// Starts the requested animation once the library contains it.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the animation was started.
+    fn start_animation(
+        &mut self,
+        entity: Entity,
+        library: &AnimationLibrary,
+        events: &mut AnimationEventWriters,
+    ) -> bool {
+        let Some(animation_name) = self.current_animation.as_deref() else {
+            return false;
+        };
+
+        // Check if animation exists, else stop playing
+        let Some(index) = library.animation_index(animation_name) else {
+            println!("\x1b[38;5;196mAnimation ({animation_name}) doesn't exist\x1b[0;0;0m");
+            self.current_animation = None;
+            return false;
+        };
+
+        let animation = library.animation_at(index);
+
+        // The frames can be removed through animation_mut
+        if animation.indices.is_empty() {
+            println!("\x1b[38;5;196mAnimation ({animation_name}) has no frames\x1b[0;0;0m");
+            self.current_animation = None;
+            return false;
+        }
+
+        self.current_animation_index = Some(index);
+        // Animations played backwards start at their last frame
+        self.current_index = if self.speed < 0f32 {animation.indices.len() - 1} else {0};
+        self.elapsed = 0f32;
+        self.passes = 0;
+        self.ping_pong_reversed = false;
+
+        // Start at the requested position, so the events are only sent for the first shown frame
+        if let Some(seek) = self.seek.take() {
+            self.seek_to(animation, seek);
+        }
+
+        events.started(entity, &animation.name);
+        events.frame_changed(entity, animation, self.current_index);
+
+        return true;
+    }
+
+    /// Starts the next queued animation that exists in the library.
+    ///
+    /// # Returns
+    ///
+    /// `true` if an animation was started.
+    fn start_next_queued(
+        &mut self,
+        entity: Entity,
+        library: &AnimationLibrary,
+        events: &mut AnimationEventWriters,
+    ) -> bool {
+        while let Some(queued) = self.queue.pop_front() {
+            self.request_animation(&queued.name, queued.times);
+
+            if self.start_animation(entity, library, events) {
+                return true;
+            }
+        }
+
+        return false;
+    }
+
+    /// Moves to the position requested by one of the seek methods.
+    ///
+    /// # Parameters
+    ///
+    /// - `animation`: Reference to the current animation.
+    /// - `seek`: The requested position.
+    fn seek_to(
+        &mut self,
+        animation: &AnimationData,
+        seek: Seek,
+    ) {
+        let (frame, time_in_frame) = match seek {
+            Seek::Frame(frame) => (frame.min(animation.indices.len() - 1), 0f32),
+            Seek::Time(time) => animation.frame_at_time(time),
+            Seek::NormalizedTime(normalized_time) => {
+                animation.frame_at_time(normalized_time.clamp(0f32, 1f32) * animation.duration())
+            },
+        };
+
+        self.current_index = frame;
+        self.animation_is_finished = false;
+
+        // Frames played backwards are entered at their end
+        let reversed = (self.speed < 0f32) != self.ping_pong_reversed;
+
+        self.elapsed = if reversed && time_in_frame > 0f32 {
+            (animation.frame_duration(frame) - time_in_frame).max(0f32)
+        } else {
+            time_in_frame
+        };
+    }
+
+    /// Applies the current frame to the sprite.
+    ///
+    /// This method sets the sprite index and the texture atlas of the page the current frame
+    /// is stored in, and converts the anchor, or the pivot of the current frame, from the
+    /// untrimmed frame to the trimmed frame, moving it by the frame and animation offsets of
+    /// the current frame. Sprites whose animation finished with `LoopMode::OnceAndHide` are
+    /// hidden until the next animation starts.
+    ///
+    /// The components are only written when their value changes, so change detection, e.g.
+    /// `Changed<TextureAtlasSprite>`, only sees frames that actually look different.
+    ///
+    /// # Parameters
+    ///
+    /// - `library`: Reference to the library containing the current animation.
     /// - `sprite`: Reference to the sprite being animated.
-    /// - `transform`: Reference to the transform of the sprite.
+    /// - `texture_atlas`: Reference to the texture atlas handle of the sprite.
+    /// - `visibility`: Reference to the visibility of the sprite, if it has one.
+    fn apply_frame(
+        &mut self,
+        library: &AnimationLibrary,
+        sprite: &mut Mut<TextureAtlasSprite>,
+        texture_atlas: &mut Mut<Handle<TextureAtlas>>,
+        visibility: Option<&mut Mut<Visibility>>,
+    ) {
+        let animation = library.animation_at(self.current_animation_index.unwrap());
+
+        let index = animation.indices[self.current_index];
+
+        let frame_info = library.frame_info(index);
+
+        // Frames of other pages are stored in other texture atlases
+        if sprite.index != frame_info.atlas_index {
+            sprite.index = frame_info.atlas_index;
+        }
+
+        let frame_atlas = library.frame_atlas(index);
+
+        if **texture_atlas != *frame_atlas {
+            **texture_atlas = frame_atlas.clone();
+        }
+
+        let mut offset = frame_info.position_offset + animation.offset;
+
+        // The pivot of the frame replaces the anchor of the AnimatedSprite
+        let pivot = frame_info.pivot.filter(|_| self.use_frame_pivots);
+
+        let mut anchor = pivot.unwrap_or(self.anchor.as_vec());
+
+        // Flipping mirrors the image around its center, so the offsets and the pivot, a point of
+        // the image, have to be mirrored too
+        if sprite.flip_x {
+            offset.x = -offset.x;
+
+            if pivot.is_some() {
+                anchor.x = -anchor.x;
+            }
+        }
+
+        if sprite.flip_y {
+            offset.y = -offset.y;
+
+            if pivot.is_some() {
+                anchor.y = -anchor.y;
+            }
+        }
+
+        // The anchor of the AnimatedSprite is relative to the untrimmed frame, the anchor of the
+        // sprite to the trimmed frame it shows
+        let anchor = (anchor * frame_info.source_size - offset) / frame_info.size.max(Vec2::ONE);
+
+        if sprite.anchor.as_vec() != anchor {
+            sprite.anchor = Anchor::Custom(anchor);
+        }
+
+        // Only change the visibility when the animation hides or shows the sprite, so it can
+        // still be hidden by other systems
+        let is_hidden = self.animation_is_finished && animation.loop_mode == LoopMode::OnceAndHide;
+
+        if self.is_hidden != is_hidden {
+            self.is_hidden = is_hidden;
+
+            if let Some(visibility) = visibility {
+                visibility.is_visible = !is_hidden;
+            }
+        }
+    }
+
+    /// Moves to the next frame of the current animation.
+    ///
+    /// This method advances the animation to the next frame, or to the previous frame
+    /// if the speed is negative or a ping-pong animation is playing back. At the end of
+    /// a pass, the loop mode of the animation decides whether it finishes or loops.
+    ///
+    /// # Parameters
+    ///
+    /// - `entity`: The entity of the animated sprite, used for the events.
+    /// - `library`: Reference to the library containing the current animation.
+    /// - `events`: The writers used to send the animation events.
     fn next_frame(
         &mut self,
-        sprite: &mut TextureAtlasSprite,
-        transform: &mut Transform,
+        entity: Entity,
+        library: &AnimationLibrary,
+        events: &mut AnimationEventWriters,
     ) {
-        let animation = &mut self.animations[self.current_animation_index.unwrap()];
-    
-        animation.timer.reset();
-    
-        if animation.current_index >= animation.indices.len() - 1 {
-            if !animation.looped {
-                self.animation_is_finished = true;
-                return;
+        let animation = library.animation_at(self.current_animation_index.unwrap());
+
+        let last_index = animation.indices.len() - 1;
+        let loop_start = animation.loop_start.min(last_index);
+        // The intro before the loop start is only played by the first pass
+        let first_index = if self.passes > 0 {loop_start} else {0};
+        let reversed = (self.speed < 0f32) != self.ping_pong_reversed;
+
+        // Animations played backwards end at their first frame
+        let is_last_frame = if reversed {self.current_index <= first_index} else {self.current_index >= last_index};
+
+        if !is_last_frame {
+            if reversed {
+                // Move to the previous frame
+                self.current_index -= 1;
+            } else {
+                // Move to the next frame
+                self.current_index += 1;
             }
-    
-            // Remove frame offset
-            transform.translation -= Vec3::new(
-                self.frame_offsets[sprite.index].position_offset.x,
-                self.frame_offsets[sprite.index].position_offset.y,
-                0f32,
-            ) * transform.scale;
-
-            // Remove frame rotation
-            transform.rotate_local_z(-self.frame_offsets[sprite.index].rotation_offset as f32);
-    
-            // Loop to the first frame
-            animation.current_index = 0;
-            sprite.index = animation.indices[animation.current_index];
-    
-            // Set frame offset
-            transform.translation += Vec3::new(
-                self.frame_offsets[sprite.index].position_offset.x,
-                self.frame_offsets[sprite.index].position_offset.y,
-                0f32,
-            ) * transform.scale;
-
-            // Set frame rotation
-            transform.rotate_local_z(self.frame_offsets[sprite.index].rotation_offset as f32);
+
+            events.frame_changed(entity, animation, self.current_index);
+            return;
+        }
+
+        self.passes += 1;
+
+        // The number of times of a queued animation overrides its loop mode,
+        // a ping-pong cycle is made of two passes
+        let is_finished = match (self.current_times, animation.loop_mode) {
+            (Some(times), LoopMode::PingPong | LoopMode::PingPongTimes(_) | LoopMode::PingPongPasses(_)) => self.passes >= times.saturating_mul(2),
+            (Some(times), _) => self.passes >= times,
+            (None, LoopMode::Once | LoopMode::OnceAndHide) => true,
+            (None, LoopMode::Loop | LoopMode::PingPong) => false,
+            (None, LoopMode::LoopTimes(times)) => self.passes >= times,
+            (None, LoopMode::PingPongTimes(times)) => self.passes >= times.saturating_mul(2),
+            (None, LoopMode::PingPongPasses(passes)) => self.passes >= passes,
+        };
+
+        if is_finished {
+            self.animation_is_finished = true;
+            events.finished(entity, &animation.name);
+            return;
+        }
+
+        if let LoopMode::PingPong | LoopMode::PingPongTimes(_) | LoopMode::PingPongPasses(_) = animation.loop_mode {
+            // Turn around without showing the last frame twice
+            self.ping_pong_reversed = !self.ping_pong_reversed;
+            self.current_index = if reversed {
+                (self.current_index + 1).min(last_index)
+            } else {
+                self.current_index.saturating_sub(1).max(loop_start)
+            };
         } else {
-            // Remove frame offset
-            transform.translation -= Vec3::new(
-                self.frame_offsets[sprite.index].position_offset.x,
-                self.frame_offsets[sprite.index].position_offset.y,
-                0f32,
-            ) * transform.scale;
-
-            // Remove frame rotation
-            transform.rotate_local_z(-self.frame_offsets[sprite.index].rotation_offset as f32);
-    
-            // Move to the next frame
-            animation.current_index += 1;
-            sprite.index = animation.indices[animation.current_index];
-    
-            // Set frame offset
-            transform.translation += Vec3::new(
-                self.frame_offsets[sprite.index].position_offset.x,
-                self.frame_offsets[sprite.index].position_offset.y,
-                0f32,
-            ) * transform.scale;
-
-            // Set frame rotation
-            transform.rotate_local_z(self.frame_offsets[sprite.index].rotation_offset as f32);
+            // Loop to the loop start in the direction of playback
+            self.current_index = if reversed {last_index} else {loop_start};
         }
+
+        events.looped(entity, &animation.name);
+        events.frame_changed(entity, animation, self.current_index);
     }
-    
+
     /// Updates the frame of the current animation.
     ///
-    /// This method updates the animation frame based on the elapsed time and advances
-    /// to the next frame if necessary.
+    /// This method starts a requested animation, applies a requested seek, or advances the
+    /// animation by the elapsed time.
+    /// If more time has passed than the current frame lasts, multiple frames are advanced and
+    /// the remaining time is carried over to the next update. When the animation finishes,
+    /// the next queued animation is started with the remaining time.
     ///
     /// # Parameters
     ///
+    /// - `entity`: The entity of the animated sprite, used for the events.
+    /// - `library`: Reference to the library containing the animations.
     /// - `sprite`: Reference to the sprite being animated.
-    /// - `transform`: Reference to the transform of the sprite.
+    /// - `texture_atlas`: Reference to the texture atlas handle of the sprite.
+    /// - `visibility`: Reference to the visibility of the sprite, if it has one.
     /// - `time`: Reference to the time information for timing the animation.
+    /// - `events`: The writers used to send the animation events.
+    #[allow(clippy::too_many_arguments)]
     fn update_frame(
         &mut self,
-        mut sprite: &mut TextureAtlasSprite, 
-        mut transform: &mut Transform,
+        entity: Entity,
+        library: &AnimationLibrary,
+        sprite: &mut Mut<TextureAtlasSprite>,
+        texture_atlas: &mut Mut<Handle<TextureAtlas>>,
+        visibility: Option<&mut Mut<Visibility>>,
         time: &Time,
+        events: &mut AnimationEventWriters,
     ) {
-        // Check if animation is finished or paused, if yes, skip
-        if self.animation_is_finished || self.animation_is_paused {
-            return;
-        }
-    
-        if let Some(index) = self.current_animation_index {
-            let animation = &mut self.animations[index];
-            animation.timer.tick(time.delta());
-    
-            if animation.timer.just_finished() {
-                self.next_frame(&mut sprite, &mut transform);
+        if self.current_animation_index.is_none() {
+            // Start a requested or queued animation, its first frame is shown for the full duration
+            if !self.start_animation(entity, library, events)
+                && !self.start_next_queued(entity, library, events) {
+                return;
+            }
+        } else if let Some(seek) = self.seek.take() {
+            let animation = library.animation_at(self.current_animation_index.unwrap());
+            let previous_index = self.current_index;
+
+            self.seek_to(animation, seek);
+
+            if self.current_index != previous_index {
+                events.frame_changed(entity, animation, self.current_index);
+            }
+        } else if self.animation_is_finished && !self.animation_is_paused {
+            // Animations queued after the current one has finished start right away
+            if !self.queue.is_empty() && !self.start_next_queued(entity, library, events) {
+                return;
+            }
+        } else if !self.animation_is_paused {
+            self.elapsed += time.delta_seconds() * self.speed.abs();
+
+            loop {
+                if self.animation_is_finished {
+                    // Continue with the next queued animation, carrying over the remaining time
+                    if self.queue.is_empty() {
+                        break;
+                    }
+
+                    let elapsed = self.elapsed;
+
+                    if !self.start_next_queued(entity, library, events) {
+                        return;
+                    }
+
+                    self.elapsed = elapsed;
+                }
+
+                let animation = library.animation_at(self.current_animation_index.unwrap());
+                let frame_duration = animation.frame_duration(self.current_index);
+
+                // Frames with an invalid duration are held forever
+                if frame_duration.is_nan() || frame_duration <= 0f32 || self.elapsed < frame_duration {
+                    break;
+                }
+
+                self.elapsed -= frame_duration;
+                self.next_frame(entity, library, events);
             }
         }
+
+        self.apply_frame(library, sprite, texture_atlas, visibility);
     }
 }
 
 // System to update animations
+//
+// Sends the AnimationStarted, AnimationLooped, AnimationFinished, FrameChanged and AnimationEvent
+// events and evaluates the transitions of AnimationStateMachines. AnimatedSprites whose library
+// was modified or reloaded keep playing their animation and send an AnimationReloaded event,
+// it is added by the SpriteSheetAnimationPlugin
+#[allow(clippy::type_complexity)]
 pub fn update_animations(
-    mut query: Query<(&mut AnimatedSprite, &mut TextureAtlasSprite, &mut Transform)>,
+    mut query: Query<(
+        Entity,
+        &mut AnimatedSprite,
+        &mut TextureAtlasSprite,
+        &mut Handle<TextureAtlas>,
+        Option<&mut Visibility>,
+        Option<&mut AnimationStateMachine>,
+    )>,
+    libraries: Res<Assets<AnimationLibrary>>,
+    mut library_events: EventReader<AssetEvent<AnimationLibrary>>,
+    graphs: Res<Assets<AnimationGraph>>,
     time: Res<Time>,
+    mut events: AnimationEventWriters,
 ) {
-    for (mut animated_sprite, mut sprite, mut transform) in query.iter_mut() {
-        animated_sprite.update_frame(&mut sprite, &mut transform, &time);
+    let reloaded: HashSet<HandleId> = library_events.iter().filter_map(|event| match event {
+        AssetEvent::Modified { handle } => Some(handle.id()),
+        _ => None,
+    }).collect();
+
+    for (entity, mut animated_sprite, mut sprite, mut texture_atlas, mut visibility, state_machine) in query.iter_mut() {
+        // Wait for the library to be loaded
+        let Some(library) = libraries.get(&animated_sprite.library) else {
+            continue;
+        };
+
+        let is_reloaded = reloaded.contains(&animated_sprite.library.id());
+
+        // The asset event of a library replaced in this update is only read in the next one,
+        // so outdated animations are looked up again right away
+        if is_reloaded || !animated_sprite.is_up_to_date(library, &texture_atlas) {
+            animated_sprite.reload(library);
+        }
+
+        if is_reloaded {
+            events.reloaded(entity, animated_sprite.current_animation());
+        }
+
+        // Take transitions before updating the frame, so the animation of a new state starts right away
+        if let Some(mut state_machine) = state_machine {
+            if let Some(graph) = graphs.get(&state_machine.graph) {
+                state_machine.update(graph, &mut animated_sprite, library);
+            }
+        }
+
+        animated_sprite.update_frame(
+            entity,
+            library,
+            &mut sprite,
+            &mut texture_atlas,
+            visibility.as_mut(),
+            &time,
+            &mut events,
+        );
     }
 }
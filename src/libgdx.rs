@@ -0,0 +1,246 @@
+// Import necessary modules and crates
+use std::path::Path;
+
+use bevy::prelude::*;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::utils::BoxedFuture;
+
+use crate::AnimationLibrary;
+use crate::SpriteSheetError;
+use crate::sprite_sheet::{self, SpriteSheetFrame, SpriteSheetPage};
+
+// A region of a page, as written in the data file
+#[derive(Debug, Default)]
+struct Region {
+    name: String,
+    position: Vec2,
+    size: Vec2,
+    // Degrees the region is stored rotated by counter clockwise, 0, 90 or 270
+    degrees: u32,
+    // Offset of the bottom left corner of the region in the untrimmed frame
+    offset: Vec2,
+    // Size of the untrimmed frame
+    original_size: Option<Vec2>,
+    index: i32,
+}
+
+impl Region {
+    // Converts the region into a sprite sheet frame
+    fn to_sprite_sheet_frame(
+        &self
+    ) -> SpriteSheetFrame {
+        // Regions with an index are frames of an animation, named like the frames of a Sparrow export
+        let name = if self.index >= 0 { format!("{}{:04}", self.name, self.index) } else { self.name.clone() };
+
+        // The size is the size of the upright region, rotated regions are stored with width and height swapped
+        let rotated = self.degrees != 0;
+        let stored_size = if rotated { Vec2::new(self.size.y, self.size.x) } else { self.size };
+
+        let original_size = self.original_size.unwrap_or(self.size);
+
+        return SpriteSheetFrame {
+            name,
+            rect: Rect::from_corners(self.position, self.position + stored_size),
+            // The offset of the center of the region from the center of the untrimmed frame, y up
+            offset: self.offset + (self.size - original_size) * 0.5,
+            source_size: original_size,
+            pivot: None,
+            rotated,
+            // libGDX rotates regions counter clockwise, unlike TexturePacker, 270 degrees
+            // counter clockwise are 90 degrees clockwise
+            counter_clockwise: self.degrees == 90,
+            page: 0,
+            duration: None,
+        };
+    }
+}
+
+// Parses the comma separated numbers of a field
+fn parse_numbers(
+    path: &Path,
+    line: usize,
+    value: &str,
+    count: usize,
+) -> Result<Vec<f32>, SpriteSheetError> {
+    let numbers: Vec<f32> = value.split(',').filter_map(|number| number.trim().parse().ok()).collect();
+
+    if numbers.len() < count {
+        return Err(
+            SpriteSheetError::Parse {
+                path: path.to_path_buf(),
+                line: Some(line),
+                column: None,
+                message: format!("expected {count} numbers, found \"{}\"", value.trim()),
+            }
+        );
+    }
+
+    return Ok(numbers);
+}
+
+// Sets a field of a region
+fn parse_field(
+    path: &Path,
+    line: usize,
+    region: &mut Region,
+    key: &str,
+    value: &str,
+) -> Result<(), SpriteSheetError> {
+    match key {
+        "xy" => {
+            let numbers = parse_numbers(path, line, value, 2)?;
+            region.position = Vec2::new(numbers[0], numbers[1]);
+        },
+        "size" => {
+            let numbers = parse_numbers(path, line, value, 2)?;
+            region.size = Vec2::new(numbers[0], numbers[1]);
+        },
+        "bounds" => {
+            let numbers = parse_numbers(path, line, value, 4)?;
+            region.position = Vec2::new(numbers[0], numbers[1]);
+            region.size = Vec2::new(numbers[2], numbers[3]);
+        },
+        "orig" => {
+            let numbers = parse_numbers(path, line, value, 2)?;
+            region.original_size = Some(Vec2::new(numbers[0], numbers[1]));
+        },
+        "offset" => {
+            let numbers = parse_numbers(path, line, value, 2)?;
+            region.offset = Vec2::new(numbers[0], numbers[1]);
+        },
+        "offsets" => {
+            let numbers = parse_numbers(path, line, value, 4)?;
+            region.offset = Vec2::new(numbers[0], numbers[1]);
+            region.original_size = Some(Vec2::new(numbers[2], numbers[3]));
+        },
+        // true in the old format, the degrees in the new one
+        "rotate" => {
+            region.degrees = match value.trim() {
+                "false" => 0,
+                "true" => 90,
+                degrees => match degrees.parse::<i32>().map(|degrees| degrees.rem_euclid(360)) {
+                    Ok(degrees @ (0 | 90 | 270)) => degrees as u32,
+                    _ => return Err(
+                        SpriteSheetError::Parse {
+                            path: path.to_path_buf(),
+                            line: Some(line),
+                            column: None,
+                            message: format!("unsupported rotation \"{degrees}\", only 90 and 270 degrees are supported"),
+                        }
+                    ),
+                },
+            };
+        },
+        "index" => region.index = value.trim().parse().unwrap_or(-1),
+        _ => {},
+    }
+
+    return Ok(());
+}
+
+// Parses the pages of the libGDX content and converts their regions into sprite sheet frames
+pub(crate) fn parse(
+    path: &Path,
+    content: &str,
+) -> Result<Vec<SpriteSheetPage>, SpriteSheetError> {
+    // Remove the BOM if present (UTF-8 BOM is 0xEF, 0xBB, 0xBF)
+    let content = content.trim_start_matches('\u{FEFF}');
+
+    let mut pages: Vec<SpriteSheetPage> = Vec::new();
+    let mut region: Option<Region> = None;
+
+    // Pages are separated by empty lines, the first line of a page is its image
+    let mut expects_page = true;
+
+    for (index, line) in content.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+
+        if line.is_empty() {
+            expects_page = true;
+            continue;
+        }
+
+        if let Some((key, value)) = line.split_once(':') {
+            // Fields before the first region belong to the page
+            if let Some(region) = region.as_mut() {
+                parse_field(path, line_number, region, key.trim(), value)?;
+            }
+
+            continue;
+        }
+
+        if let (Some(region), Some(page)) = (region.take(), pages.last_mut()) {
+            page.frames.push(region.to_sprite_sheet_frame());
+        }
+
+        if expects_page {
+            expects_page = false;
+            pages.push(SpriteSheetPage { image_path: Some(line.to_string()), frames: Vec::new() });
+        } else {
+            region = Some(Region { name: line.to_string(), index: -1, ..default() });
+        }
+    }
+
+    if let (Some(region), Some(page)) = (region.take(), pages.last_mut()) {
+        page.frames.push(region.to_sprite_sheet_frame());
+    }
+
+    return Ok(pages);
+}
+
+pub fn create_animation_library(
+    path: &str,
+    texture_atlases: &mut Assets<TextureAtlas>,
+    asset_server: &AssetServer,
+) -> Result<AnimationLibrary, SpriteSheetError> {
+    let data_path = format!("{path}.atlas");
+
+    let data_path = Path::new(&data_path);
+
+    // Load libGDX content from file
+    let content = sprite_sheet::read_data_file(asset_server, data_path)?;
+
+    let pages = parse(data_path, &content)?;
+
+    return sprite_sheet::create_animation_library(
+        data_path,
+        pages,
+        texture_atlases,
+        asset_server,
+    );
+}
+
+/// Asset loader for the libGDX and Spine data format.
+///
+/// Loads `.atlas` files in the old and the new format as a [`SpriteSheet`](crate::SpriteSheet).
+/// The images of the pages are loaded as dependencies, relative to the data file. Regions with
+/// an `index` are named like the frames of a Sparrow export, e.g. `run0003`, so animations can
+/// be added by prefix.
+///
+/// The texture atlas of the first page is available as the labeled asset `atlas`, the ones of
+/// the following pages as `atlas1`, `atlas2`, ...
+#[derive(Debug, Default)]
+pub struct LibGdxLoader;
+
+impl AssetLoader for LibGdxLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let content = std::str::from_utf8(bytes)?;
+
+            let pages = parse(load_context.path(), content)?;
+
+            sprite_sheet::set_sprite_sheet_asset(load_context, pages, Vec::new(), Vec::new())?;
+
+            return Ok(());
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        return &["atlas"];
+    }
+}
@@ -0,0 +1,66 @@
+// Import necessary modules and crates
+use std::path::Path;
+
+use bevy::prelude::*;
+
+use serde::Deserialize;
+
+use crate::AnimationLibrary;
+use crate::SpriteSheetError;
+use crate::json_array::NamedFrameData;
+use crate::sprite_sheet::{self, SpriteSheetPage};
+
+#[derive(Debug, Default, Deserialize)]
+struct Texture {
+    image: Option<String>,
+    #[serde(default)]
+    frames: Vec<NamedFrameData>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Textures {
+    textures: Vec<Texture>,
+}
+
+// Deserializes the Phaser 3 content and converts the frames of every texture into a page
+pub(crate) fn parse(
+    path: &Path,
+    content: &str,
+) -> Result<Vec<SpriteSheetPage>, SpriteSheetError> {
+    // Remove the BOM if present (UTF-8 BOM is 0xEF, 0xBB, 0xBF)
+    let content = content.trim_start_matches('\u{FEFF}');
+
+    // Deserialize Json data
+    let json_data: Textures = serde_json::from_str(content).map_err(|error| SpriteSheetError::from_json(path, error))?;
+
+    let pages = json_data.textures.iter().map(|texture| {
+        return SpriteSheetPage {
+            image_path: texture.image.clone(),
+            frames: texture.frames.iter().map(|frame| frame.data.to_sprite_sheet_frame(&frame.filename)).collect(),
+        };
+    }).collect();
+
+    return Ok(pages);
+}
+
+pub fn create_animation_library(
+    path: &str,
+    texture_atlases: &mut Assets<TextureAtlas>,
+    asset_server: &AssetServer,
+) -> Result<AnimationLibrary, SpriteSheetError> {
+    let data_path = format!("{path}.json");
+
+    let data_path = Path::new(&data_path);
+
+    // Load Json content from file
+    let content = sprite_sheet::read_data_file(asset_server, data_path)?;
+
+    let pages = parse(data_path, &content)?;
+
+    return sprite_sheet::create_animation_library(
+        data_path,
+        pages,
+        texture_atlases,
+        asset_server,
+    );
+}
@@ -0,0 +1,129 @@
+// Import necessary modules and crates
+use bevy::prelude::*;
+use bevy::ecs::schedule::StageLabelId;
+use bevy::transform::TransformSystem;
+
+use crate::{
+    AnimationEvent, AnimationFinished, AnimationGraph, AnimationGraphLoader, AnimationLibrary,
+    AnimationLibraryLoader, AnimationLooped, AnimationReloaded, AnimationStarted, AsepriteLoader,
+    Cocos2dLoader, FrameChanged, FrameEvents, FrameEventsLoader, JsonLoader, LibGdxLoader, SparrowLoader,
+    SpriteSheet,
+};
+use crate::update_animations;
+use crate::composed_frames::{ComposedAtlases, bake_composed_frames};
+use crate::reload::reload_sprite_sheets;
+use crate::rotated_frames::{RotatedAtlases, bake_rotated_frames};
+
+/// Labels of the systems added by the `SpriteSheetAnimationPlugin`.
+///
+/// Use them to order your own systems, e.g. `my_state_machine.before(SpriteSheetAnimationSystem::UpdateAnimations)`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, SystemLabel)]
+pub enum SpriteSheetAnimationSystem {
+    /// The `update_animations` system.
+    UpdateAnimations,
+    /// The system updating the `AnimationLibrary` assets whose `SpriteSheet` was reloaded.
+    ReloadSpriteSheets,
+    /// The system drawing the frames of Adobe Animate texture atlases.
+    BakeComposedFrames,
+    /// The system copying frames stored rotated in their image upright.
+    BakeRotatedFrames,
+}
+
+/// Plugin registering everything needed to play animations.
+///
+/// This plugin adds the `SpriteSheet`, `AnimationLibrary`, `FrameEvents` and `AnimationGraph`
+/// assets with their loaders, the animation events and the `update_animations` system labeled with
+/// `SpriteSheetAnimationSystem::UpdateAnimations`. Right before it, the libraries whose sprite
+/// sheet was reloaded are updated. Frames stored rotated in their image are copied upright
+/// and the frames of Adobe Animate texture atlases are drawn from their parts once the images
+/// are loaded, so they are shown like every other frame.
+///
+/// # Fields
+///
+/// - `stage`: The stage `update_animations` runs in, `CoreStage::Update` by default.
+#[derive(Debug, Clone)]
+pub struct SpriteSheetAnimationPlugin {
+    /// The stage `update_animations` runs in.
+    pub stage: StageLabelId,
+}
+
+impl Default for SpriteSheetAnimationPlugin {
+    fn default() -> Self {
+        return SpriteSheetAnimationPlugin {
+            stage: CoreStage::Update.as_label(),
+        };
+    }
+}
+
+impl SpriteSheetAnimationPlugin {
+    /// Creates a `SpriteSheetAnimationPlugin` that runs `update_animations` in the given stage.
+    ///
+    /// # Parameters
+    ///
+    /// - `stage`: The stage `update_animations` runs in.
+    pub fn in_stage(
+        stage: impl StageLabel,
+    ) -> Self {
+        return SpriteSheetAnimationPlugin {
+            stage: stage.as_label(),
+        };
+    }
+}
+
+impl Plugin for SpriteSheetAnimationPlugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.add_asset::<SpriteSheet>()
+            .add_asset::<AnimationLibrary>()
+            .add_asset::<FrameEvents>()
+            .add_asset::<AnimationGraph>()
+            .init_asset_loader::<SparrowLoader>()
+            .init_asset_loader::<JsonLoader>()
+            .init_asset_loader::<AsepriteLoader>()
+            .init_asset_loader::<Cocos2dLoader>()
+            .init_asset_loader::<LibGdxLoader>()
+            .init_asset_loader::<AnimationLibraryLoader>()
+            .init_asset_loader::<FrameEventsLoader>()
+            .init_asset_loader::<AnimationGraphLoader>()
+            .add_event::<AnimationStarted>()
+            .add_event::<AnimationLooped>()
+            .add_event::<AnimationFinished>()
+            .add_event::<FrameChanged>()
+            .add_event::<AnimationEvent>()
+            .add_event::<AnimationReloaded>()
+            .init_resource::<RotatedAtlases>()
+            .init_resource::<ComposedAtlases>();
+
+        app.add_system_to_stage(
+            self.stage,
+            reload_sprite_sheets
+                .label(SpriteSheetAnimationSystem::ReloadSpriteSheets)
+                .before(SpriteSheetAnimationSystem::UpdateAnimations),
+        );
+
+        app.add_system_to_stage(
+            self.stage,
+            bake_rotated_frames
+                .label(SpriteSheetAnimationSystem::BakeRotatedFrames)
+                .after(SpriteSheetAnimationSystem::ReloadSpriteSheets),
+        );
+
+        app.add_system_to_stage(
+            self.stage,
+            bake_composed_frames
+                .label(SpriteSheetAnimationSystem::BakeComposedFrames)
+                .after(SpriteSheetAnimationSystem::ReloadSpriteSheets),
+        );
+
+        let system = update_animations.label(SpriteSheetAnimationSystem::UpdateAnimations);
+
+        // Make sure the transforms are propagated after the animations were updated
+        if self.stage == CoreStage::PostUpdate.as_label() {
+            app.add_system_to_stage(self.stage, system.before(TransformSystem::TransformPropagate));
+        } else {
+            app.add_system_to_stage(self.stage, system);
+        }
+    }
+}
@@ -0,0 +1,38 @@
+// Import necessary modules and crates
+use bevy::prelude::*;
+use bevy::asset::HandleId;
+
+use crate::AnimationLibrary;
+use crate::SpriteSheet;
+
+// System updating the AnimationLibraries whose SpriteSheet was reloaded
+//
+// The AnimatedSprites using the libraries are updated by update_animations, which also sends
+// the AnimationReloaded events, it is added by the SpriteSheetAnimationPlugin
+pub fn reload_sprite_sheets(
+    mut sprite_sheet_events: EventReader<AssetEvent<SpriteSheet>>,
+    sprite_sheets: Res<Assets<SpriteSheet>>,
+    mut libraries: ResMut<Assets<AnimationLibrary>>,
+) {
+    for event in sprite_sheet_events.iter() {
+        let AssetEvent::Modified { handle } = event else {
+            continue;
+        };
+
+        let Some(sprite_sheet) = sprite_sheets.get(handle) else {
+            continue;
+        };
+
+        // Only the libraries using the sprite sheet are modified, so no other library sends an event
+        let reloaded: Vec<HandleId> = libraries.iter()
+            .filter(|(_, library)| library.sprite_sheet == *handle)
+            .map(|(id, _)| id)
+            .collect();
+
+        for id in reloaded {
+            if let Some(library) = libraries.get_mut(&Handle::weak(id)) {
+                library.reload_sprite_sheet(sprite_sheet);
+            }
+        }
+    }
+}
@@ -0,0 +1,293 @@
+// Import necessary modules and crates
+use std::collections::{HashMap, HashSet};
+
+use bevy::prelude::*;
+use bevy::asset::HandleId;
+use bevy::render::render_resource::{Extent3d, TextureDimension};
+
+use crate::AnimationLibrary;
+use crate::SpriteSheet;
+
+// A frame stored rotated in the image of a texture atlas
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct RotatedFrame {
+    // Index of the frame in the texture atlas
+    index: usize,
+    counter_clockwise: bool,
+}
+
+// A texture atlas containing rotated frames
+#[derive(Debug, Default)]
+struct RotatedAtlas {
+    frames: Vec<RotatedFrame>,
+    // The loaded image and the rects the rotated frames are stored in
+    source: Option<(Handle<Image>, Vec<Rect>)>,
+    // The copy of the image with the rotated frames added upright
+    baked: Option<Handle<Image>>,
+}
+
+// The texture atlases whose rotated frames are baked upright by bake_rotated_frames
+#[derive(Debug, Default, Resource)]
+pub(crate) struct RotatedAtlases {
+    atlases: HashMap<HandleId, RotatedAtlas>,
+}
+
+impl RotatedAtlases {
+    // Adds the rotated frames of the texture atlases
+    fn register<'a>(
+        &mut self,
+        frames: impl Iterator<Item = (&'a Handle<TextureAtlas>, usize, bool)>,
+    ) {
+        let mut atlases: HashMap<HandleId, Vec<RotatedFrame>> = HashMap::new();
+
+        for (texture_atlas, index, counter_clockwise) in frames {
+            atlases.entry(texture_atlas.id()).or_default().push(RotatedFrame { index, counter_clockwise });
+        }
+
+        for (id, frames) in atlases {
+            let rotated_atlas = self.atlases.entry(id).or_default();
+
+            if rotated_atlas.frames != frames {
+                rotated_atlas.frames = frames;
+                // The frames have to be read from the texture atlas again
+                rotated_atlas.source = None;
+            }
+        }
+    }
+}
+
+// Returns the texture atlas, the index in it and the direction of every rotated frame of the sprite sheet
+fn sprite_sheet_rotated_frames(
+    sprite_sheet: &SpriteSheet,
+) -> impl Iterator<Item = (&Handle<TextureAtlas>, usize, bool)> {
+    // The frames of every page are stored in its texture atlas in order
+    let mut page_sizes: Vec<usize> = vec![0; sprite_sheet.pages.len()];
+
+    return sprite_sheet.frames.iter().filter_map(move |frame| {
+        let texture_atlas = sprite_sheet.pages.get(frame.page)?;
+
+        let index = page_sizes[frame.page];
+        page_sizes[frame.page] += 1;
+
+        return frame.rotated.then_some((texture_atlas, index, frame.counter_clockwise));
+    });
+}
+
+// Creates a copy of the image with the rotated frames added upright below the original pixels
+//
+// Returns the copy and the rects of the upright frames, or None if the pixels of the image
+// can't be copied one by one, e.g. because it is compressed
+fn bake_upright(
+    image: &Image,
+    frames: &[RotatedFrame],
+    rects: &[Rect],
+) -> Option<(Image, Vec<Rect>)> {
+    let format_info = image.texture_descriptor.format.describe();
+
+    if format_info.block_dimensions != (1, 1) {
+        return None;
+    }
+
+    let pixel_size = format_info.block_size as usize;
+    let width = image.texture_descriptor.size.width as usize;
+    let height = image.texture_descriptor.size.height as usize;
+
+    if image.data.len() < width * height * pixel_size {
+        return None;
+    }
+
+    // Rotated frames are stored with width and height swapped
+    let sizes: Vec<(usize, usize)> = rects.iter().map(|rect| (rect.height() as usize, rect.width() as usize)).collect();
+
+    let baked_width = sizes.iter().map(|size| size.0).max().unwrap_or(0).max(width);
+
+    // Place the upright frames in rows below the original pixels
+    let mut positions = Vec::new();
+    let (mut x, mut y, mut row_height) = (0, height, 0);
+
+    for &(frame_width, frame_height) in sizes.iter() {
+        if x + frame_width > baked_width {
+            x = 0;
+            y += row_height;
+            row_height = 0;
+        }
+
+        positions.push((x, y));
+
+        x += frame_width;
+        row_height = row_height.max(frame_height);
+    }
+
+    let baked_height = y + row_height;
+
+    let mut data = vec![0u8; baked_width * baked_height * pixel_size];
+
+    for row in 0..height {
+        let source = row * width * pixel_size;
+        let target = row * baked_width * pixel_size;
+
+        data[target..target + width * pixel_size].copy_from_slice(&image.data[source..source + width * pixel_size]);
+    }
+
+    let mut upright_rects = Vec::new();
+
+    for (((frame, rect), &(frame_width, frame_height)), &(frame_x, frame_y)) in frames.iter().zip(rects).zip(&sizes).zip(&positions) {
+        let (rect_x, rect_y) = (rect.min.x as usize, rect.min.y as usize);
+
+        for y in 0..frame_height {
+            for x in 0..frame_width {
+                // Position of the upright pixel in the rotated frame
+                let (stored_x, stored_y) = if frame.counter_clockwise {
+                    (y, frame_width - 1 - x)
+                } else {
+                    (frame_height - 1 - y, x)
+                };
+
+                let (source_x, source_y) = (rect_x + stored_x, rect_y + stored_y);
+
+                if source_x >= width || source_y >= height {
+                    continue;
+                }
+
+                let source = (source_y * width + source_x) * pixel_size;
+                let target = ((frame_y + y) * baked_width + frame_x + x) * pixel_size;
+
+                data[target..target + pixel_size].copy_from_slice(&image.data[source..source + pixel_size]);
+            }
+        }
+
+        upright_rects.push(
+            Rect::new(
+                frame_x as f32,
+                frame_y as f32,
+                (frame_x + frame_width) as f32,
+                (frame_y + frame_height) as f32,
+            )
+        );
+    }
+
+    let mut baked = Image::new(
+        Extent3d {
+            width: baked_width as u32,
+            height: baked_height as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        image.texture_descriptor.format,
+    );
+
+    baked.sampler_descriptor = image.sampler_descriptor.clone();
+
+    return Some((baked, upright_rects));
+}
+
+// System showing rotated frames upright
+//
+// Frames stored rotated by 90 degrees are copied upright into a new image once the image of
+// their texture atlas is loaded, and the texture atlas is changed to use it. The frames are
+// baked again when the image or the sprite sheet is reloaded, it is added by the
+// SpriteSheetAnimationPlugin
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn bake_rotated_frames(
+    mut rotated_atlases: ResMut<RotatedAtlases>,
+    mut sprite_sheet_events: EventReader<AssetEvent<SpriteSheet>>,
+    mut library_events: EventReader<AssetEvent<AnimationLibrary>>,
+    mut texture_atlas_events: EventReader<AssetEvent<TextureAtlas>>,
+    mut image_events: EventReader<AssetEvent<Image>>,
+    sprite_sheets: Res<Assets<SpriteSheet>>,
+    libraries: Res<Assets<AnimationLibrary>>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    for event in sprite_sheet_events.iter() {
+        if let AssetEvent::Created { handle } | AssetEvent::Modified { handle } = event {
+            if let Some(sprite_sheet) = sprite_sheets.get(handle) {
+                rotated_atlases.register(sprite_sheet_rotated_frames(sprite_sheet));
+            }
+        }
+    }
+
+    for event in library_events.iter() {
+        if let AssetEvent::Created { handle } | AssetEvent::Modified { handle } = event {
+            if let Some(library) = libraries.get(handle) {
+                rotated_atlases.register(library.rotated_frames());
+            }
+        }
+    }
+
+    for event in texture_atlas_events.iter() {
+        if let AssetEvent::Removed { handle } = event {
+            rotated_atlases.atlases.remove(&handle.id());
+        }
+    }
+
+    let modified_images: HashSet<HandleId> = image_events.iter().filter_map(|event| match event {
+        AssetEvent::Modified { handle } => Some(handle.id()),
+        _ => None,
+    }).collect();
+
+    for (id, rotated_atlas) in rotated_atlases.atlases.iter_mut() {
+        let Some(texture_atlas) = texture_atlases.get(&Handle::weak(*id)) else {
+            continue;
+        };
+
+        let is_baked = rotated_atlas.baked.as_ref() == Some(&texture_atlas.texture);
+
+        if is_baked {
+            // Bake the frames again if the original image was reloaded
+            let is_modified = rotated_atlas.source.as_ref().is_some_and(|(image, _)| modified_images.contains(&image.id()));
+
+            if !is_modified {
+                continue;
+            }
+        } else {
+            // The texture atlas was created or reloaded, the frames are stored in its image
+            let rects = rotated_atlas.frames.iter()
+                .map(|frame| texture_atlas.textures.get(frame.index).copied().unwrap_or_default())
+                .collect();
+
+            rotated_atlas.source = Some((texture_atlas.texture.clone(), rects));
+        }
+
+        let Some((source, rects)) = rotated_atlas.source.as_ref() else {
+            continue;
+        };
+
+        // Wait for the image to be loaded
+        let Some(image) = images.get(source) else {
+            continue;
+        };
+
+        let Some((baked_image, upright_rects)) = bake_upright(image, &rotated_atlas.frames, rects) else {
+            println!("\x1b[38;5;196mRotated frames of a compressed image can't be shown upright\x1b[0;0;0m");
+            rotated_atlas.baked = Some(texture_atlas.texture.clone());
+            continue;
+        };
+
+        let size = Vec2::new(baked_image.texture_descriptor.size.width as f32, baked_image.texture_descriptor.size.height as f32);
+
+        let baked = match rotated_atlas.baked.clone() {
+            Some(baked) if baked != *source => {
+                images.set_untracked(baked.clone(), baked_image);
+                baked
+            },
+            _ => images.add(baked_image),
+        };
+
+        rotated_atlas.baked = Some(baked.clone());
+
+        let Some(texture_atlas) = texture_atlases.get_mut(&Handle::weak(*id)) else {
+            continue;
+        };
+
+        texture_atlas.texture = baked;
+        texture_atlas.size = size;
+
+        for (frame, rect) in rotated_atlas.frames.iter().zip(upright_rects) {
+            if let Some(texture) = texture_atlas.textures.get_mut(frame.index) {
+                *texture = rect;
+            }
+        }
+    }
+}
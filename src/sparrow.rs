@@ -1,12 +1,15 @@
 // Import necessary modules and crates
+use std::path::Path;
+
 use bevy::prelude::*;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::utils::BoxedFuture;
 
 use serde::Deserialize;
-use serde_xml_rs;
 
-use crate::AnimatedSpriteBundle;
-use crate::AnimatedSprite;
-use crate::FrameOffset;
+use crate::AnimationLibrary;
+use crate::SpriteSheetError;
+use crate::sprite_sheet::{self, SpriteSheetFrame, SpriteSheetPage};
 
 // Struct representing a subtexture within the XML data
 #[derive(Debug, Deserialize, PartialEq)]
@@ -25,84 +28,170 @@ struct SubTexture {
     frame_x: i32,
     #[serde(default, rename = "frameY")]
     frame_y: i32,
-    // we dont need these
-    // #[serde(default, rename = "frameWidth")]
-    // frame_width: u32,
-    // #[serde(default, rename = "frameHeight")]
-    // frame_height: u32,
+    // Sparrow v2 stores rotated subtextures with width and height swapped
+    #[serde(default)]
+    rotated: bool,
+    // Size of the untrimmed frame, missing if the subtexture isn't trimmed
+    #[serde(default, rename = "frameWidth")]
+    frame_width: Option<u32>,
+    #[serde(default, rename = "frameHeight")]
+    frame_height: Option<u32>,
+    // Sparrow v2 pivot in pixels, relative to the top left corner of the untrimmed frame
+    #[serde(default, rename = "pivotX")]
+    pivot_x: Option<f32>,
+    #[serde(default, rename = "pivotY")]
+    pivot_y: Option<f32>,
+}
+
+impl SubTexture {
+    // Returns the size of the upright subtexture
+    fn size(
+        &self
+    ) -> Vec2 {
+        if self.rotated {
+            return Vec2::new(self.height as f32, self.width as f32);
+        }
+
+        return Vec2::new(self.width as f32, self.height as f32);
+    }
+
+    // Returns the size of the untrimmed frame
+    fn source_size(
+        &self
+    ) -> Vec2 {
+        let size = self.size();
+
+        return Vec2::new(
+            self.frame_width.map_or(size.x, |frame_width| frame_width as f32),
+            self.frame_height.map_or(size.y, |frame_height| frame_height as f32),
+        );
+    }
+
+    // Returns the offset of the center of the subtexture from the center of the untrimmed frame, y up
+    fn offset(
+        &self
+    ) -> Vec2 {
+        // frameX and frameY are the position of the untrimmed frame relative to the subtexture
+        let position = Vec2::new(-self.frame_x as f32, -self.frame_y as f32);
+
+        let offset = position + (self.size() - self.source_size()) * 0.5;
+
+        return Vec2::new(offset.x, -offset.y);
+    }
+
+    // Returns the pivot relative to the untrimmed frame like Anchor::Custom, if the subtexture has one
+    fn pivot(
+        &self
+    ) -> Option<Vec2> {
+        if self.pivot_x.is_none() && self.pivot_y.is_none() {
+            return None;
+        }
+
+        let source_size = self.source_size().max(Vec2::ONE);
+
+        // A missing coordinate is centered
+        let pivot = Vec2::new(
+            self.pivot_x.unwrap_or(source_size.x * 0.5),
+            self.pivot_y.unwrap_or(source_size.y * 0.5),
+        ) / source_size;
+
+        return Some(Vec2::new(pivot.x - 0.5, 0.5 - pivot.y));
+    }
 }
 
 // Struct representing the entire XML data
 #[derive(Debug, Default, Deserialize, PartialEq)]
 struct Frames {
+    #[serde(default, rename = "imagePath")]
+    image_path: Option<String>,
     #[serde(rename = "SubTexture")]
     subtextures: Vec<SubTexture>,
 }
 
-pub fn create_animated_sprite_bundle(
+// Deserializes the XML content and converts the subtextures into sprite sheet frames
+pub(crate) fn parse(
+    path: &Path,
+    content: &str,
+) -> Result<(Option<String>, Vec<SpriteSheetFrame>), SpriteSheetError> {
+    // Remove the BOM if present (UTF-8 BOM is 0xEF, 0xBB, 0xBF)
+    let content = content.trim_start_matches('\u{FEFF}');
+
+    // Deserialize XML data
+    let xml_data: Frames = serde_xml_rs::from_str(content).map_err(|error| SpriteSheetError::from_xml(path, error))?;
+
+    let frames = xml_data.subtextures.iter().map(|subtexture| {
+        SpriteSheetFrame {
+            name: subtexture.name.clone(),
+            rect: Rect::new(
+                subtexture.x as f32,
+                subtexture.y as f32,
+                (subtexture.x + subtexture.width) as f32,
+                (subtexture.y + subtexture.height) as f32,
+            ),
+            offset: subtexture.offset(),
+            source_size: subtexture.source_size(),
+            pivot: subtexture.pivot(),
+            rotated: subtexture.rotated,
+            counter_clockwise: false,
+            page: 0,
+            duration: None,
+        }
+    }).collect();
+
+    return Ok((xml_data.image_path, frames));
+}
+
+pub fn create_animation_library(
     path: &str,
     texture_atlases: &mut Assets<TextureAtlas>,
     asset_server: &AssetServer,
-) -> Option<AnimatedSpriteBundle> {
+) -> Result<AnimationLibrary, SpriteSheetError> {
+    let data_path = format!("{path}.xml");
+
+    let data_path = Path::new(&data_path);
+
     // Load XML content from file
-    let content = std::fs::read_to_string(format!("assets/{path}.xml")).ok()?;
+    let content = sprite_sheet::read_data_file(asset_server, data_path)?;
 
-    // Remove the BOM if present (UTF-8 BOM is 0xEF, 0xBB, 0xBF)
-    let content = content.trim_start_matches('\u{FEFF}').to_string();
+    let pages = SpriteSheetPage::single(parse(data_path, &content)?);
 
-    // Deserialize XML data
-    let xml_data: Frames = serde_xml_rs::from_str(&content).ok()?;
-
-    // Load texture atlas and prepare sprite sheet bundle
-    let texture_atlas_handle = texture_atlases.add(
-        TextureAtlas::new_empty(
-            asset_server.load(format!("{path}.png")), 
-            Vec2::default()
-        )
+    return sprite_sheet::create_animation_library(
+        data_path,
+        pages,
+        texture_atlases,
+        asset_server,
     );
+}
 
-    let texture_atlas = texture_atlases.get_mut(&texture_atlas_handle)?;
+/// Asset loader for the Sparrow v1, Sparrow v2 and Starling data formats.
+///
+/// Loads `.xml` files as a [`SpriteSheet`](crate::SpriteSheet). The image is loaded as a
+/// dependency using the `imagePath` attribute of the `TextureAtlas` root, relative to the
+/// `.xml` file. If the attribute is missing, a `.png` with the same name as the `.xml` file
+/// is used instead.
+///
+/// The texture atlas is available as the labeled asset `atlas`.
+#[derive(Debug, Default)]
+pub struct SparrowLoader;
 
-    // Prepare animated sprite data
-    let mut animated_sprite = AnimatedSprite::default();
+impl AssetLoader for SparrowLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let content = std::str::from_utf8(bytes)?;
 
-    // Add subtextures to the texture atlas and animated sprite data
-    for subtexture in xml_data.subtextures.iter() {
-        // Add texture to atlas
-        let index = texture_atlas.add_texture(
-            Rect::new(
-                subtexture.x as f32,
-                subtexture.y as f32,
-                (subtexture.x + subtexture.width) as f32,
-                (subtexture.y + subtexture.height) as f32,
-            )
-        );
-        
-        // Insert texture index into frames and set frame offset
-        animated_sprite.frames.insert(
-            subtexture.name.clone(),
-            index
-        );
+            let pages = SpriteSheetPage::single(parse(load_context.path(), content)?);
 
-        animated_sprite.frame_offsets.insert(
-            index,
-            FrameOffset {
-                position_offset: Vec2::new(
-                    subtexture.frame_x as f32 * 0.5,
-                    subtexture.frame_y as f32 * 0.5,
-                ),
-                rotation_offset: 0f32
-            }
-        );
+            sprite_sheet::set_sprite_sheet_asset(load_context, pages, Vec::new(), Vec::new())?;
+
+            return Ok(());
+        })
     }
 
-    return Some(
-        AnimatedSpriteBundle {
-            sprite_sheet_bundle: SpriteSheetBundle {
-                texture_atlas: texture_atlas_handle,
-                ..default()
-            },
-            animated_sprite: animated_sprite,
-        }
-    );
-}
\ No newline at end of file
+    fn extensions(&self) -> &[&str] {
+        return &["xml"];
+    }
+}
@@ -0,0 +1,351 @@
+// Import necessary modules and crates
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use bevy::asset::{AssetPath, LoadContext, LoadedAsset};
+use bevy::reflect::TypeUuid;
+
+use crate::AnimationLibrary;
+use crate::FrameTag;
+use crate::Slice;
+use crate::SpriteSheetError;
+use crate::sparrow;
+use crate::json;
+use crate::cocos2d;
+use crate::libgdx;
+
+/// Struct representing a single frame of a sprite sheet.
+///
+/// # Fields
+///
+/// - `name`: The name of the frame as written in the data file.
+/// - `rect`: The area of the texture the frame is stored in, in pixels. Width and height of
+///   rotated frames are swapped.
+/// - `offset`: The offset of the center of the frame from the center of the untrimmed frame, in
+///   pixels with y pointing up.
+/// - `source_size`: The size of the untrimmed frame, in pixels.
+/// - `pivot`: The pivot of the frame relative to the untrimmed frame, like `Anchor::Custom`, if
+///   the data file specifies one.
+/// - `rotated`: Indicates whether the frame is stored rotated by 90 degrees clockwise in the texture.
+/// - `counter_clockwise`: Indicates whether a rotated frame is stored rotated counter clockwise
+///   instead, as in libGDX atlases.
+/// - `page`: The index of the page the frame is stored in, 0 unless the sprite sheet has several images.
+/// - `duration`: The duration of the frame in seconds, if the data file specifies one.
+#[derive(Debug, Default, Clone)]
+pub struct SpriteSheetFrame {
+    /// The name of the frame as written in the data file.
+    pub name: String,
+    /// The area of the texture the frame is stored in, in pixels. Width and height of rotated
+    /// frames are swapped.
+    pub rect: Rect,
+    /// The offset of the center of the frame from the center of the untrimmed frame, in pixels
+    /// with y pointing up.
+    pub offset: Vec2,
+    /// The size of the untrimmed frame, in pixels.
+    pub source_size: Vec2,
+    /// The pivot of the frame relative to the untrimmed frame, like `Anchor::Custom`, if the data
+    /// file specifies one.
+    pub pivot: Option<Vec2>,
+    /// Indicates whether the frame is stored rotated by 90 degrees clockwise in the texture.
+    pub rotated: bool,
+    /// Indicates whether a rotated frame is stored rotated counter clockwise instead, as in
+    /// libGDX atlases.
+    pub counter_clockwise: bool,
+    /// The index of the page the frame is stored in, 0 unless the sprite sheet has several images.
+    pub page: usize,
+    /// The duration of the frame in seconds, if the data file specifies one.
+    pub duration: Option<f32>,
+}
+
+// A page of a sprite sheet, an image and the frames stored in it
+#[derive(Debug, Default)]
+pub(crate) struct SpriteSheetPage {
+    pub(crate) image_path: Option<String>,
+    pub(crate) frames: Vec<SpriteSheetFrame>,
+}
+
+impl SpriteSheetPage {
+    // Creates the only page of a sprite sheet from the image and the frames of a data file
+    pub(crate) fn single(
+        (image_path, frames): (Option<String>, Vec<SpriteSheetFrame>),
+    ) -> Vec<SpriteSheetPage> {
+        return vec![SpriteSheetPage { image_path, frames }];
+    }
+}
+
+impl SpriteSheetFrame {
+    /// Returns the size of the frame when it is shown upright, in pixels.
+    pub fn size(
+        &self
+    ) -> Vec2 {
+        let size = self.rect.size();
+
+        if self.rotated {
+            return Vec2::new(size.y, size.x);
+        }
+
+        return size;
+    }
+}
+
+/// Asset containing the data of a sprite sheet.
+///
+/// A `SpriteSheet` is produced by the asset loaders of this crate. Large sprite sheets can be
+/// split across several images, the pages. Every page has its own `TextureAtlas`, containing
+/// the frames of the page in the same order as in `frames`.
+///
+/// # Fields
+///
+/// - `texture`: The handle to the image of the first page.
+/// - `texture_atlas`: The handle to the texture atlas of the first page.
+/// - `pages`: The handles to the texture atlases of every page, starting with `texture_atlas`.
+/// - `frames`: The frames of every page of the sprite sheet.
+/// - `tags`: The frame tags of the sprite sheet, e.g. the tags of an Aseprite file.
+/// - `slices`: The slices of the sprite sheet, e.g. the slices of an Aseprite file.
+#[derive(Debug, Clone, TypeUuid)]
+#[uuid = "5d8c0f0e-5b1f-4c3a-9a7e-3f2b6c1d8e41"]
+pub struct SpriteSheet {
+    /// The handle to the image of the first page.
+    pub texture: Handle<Image>,
+    /// The handle to the texture atlas of the first page.
+    pub texture_atlas: Handle<TextureAtlas>,
+    /// The handles to the texture atlases of every page, starting with `texture_atlas`.
+    pub pages: Vec<Handle<TextureAtlas>>,
+    /// The frames of every page of the sprite sheet.
+    pub frames: Vec<SpriteSheetFrame>,
+    /// The frame tags of the sprite sheet, e.g. the tags of an Aseprite file.
+    pub tags: Vec<FrameTag>,
+    /// The slices of the sprite sheet, e.g. the slices of an Aseprite file.
+    pub slices: Vec<Slice>,
+}
+
+/// Creates a `TextureAtlas` containing the rects of the given frames.
+pub(crate) fn create_texture_atlas(
+    texture: Handle<Image>,
+    frames: &[SpriteSheetFrame],
+) -> TextureAtlas {
+    // The size of the atlas is only known once the image is loaded,
+    // so the smallest size containing every frame is used instead
+    let size = frames.iter().fold(Vec2::ZERO, |size, frame| size.max(frame.rect.max));
+
+    let mut texture_atlas = TextureAtlas::new_empty(texture, size);
+
+    for frame in frames.iter() {
+        texture_atlas.add_texture(frame.rect);
+    }
+
+    return texture_atlas;
+}
+
+/// Resolves the path of the image relative to the data file.
+///
+/// If the data file doesn't specify an image, a `.png` with the same name as the data file is used.
+pub(crate) fn resolve_image_path(
+    data_path: &Path,
+    image_path: Option<&str>,
+) -> PathBuf {
+    return match image_path {
+        Some(image_path) => data_path.parent().unwrap_or(Path::new("")).join(image_path),
+        None => data_path.with_extension("png"),
+    };
+}
+
+/// Checks that the sprite sheet contains at least one frame.
+pub(crate) fn check_frames(
+    data_path: &Path,
+    frames: &[SpriteSheetFrame],
+) -> Result<(), SpriteSheetError> {
+    if frames.is_empty() {
+        return Err(SpriteSheetError::EmptySheet { path: data_path.to_path_buf() });
+    }
+
+    return Ok(());
+}
+
+/// Reads the bytes of a data file through the `AssetIo` of the `AssetServer`, so the asset
+/// folder of the `AssetPlugin` is used on every platform. The calling thread is blocked until
+/// the file is read.
+pub(crate) fn read_data_bytes(
+    asset_server: &AssetServer,
+    data_path: &Path,
+) -> Result<Vec<u8>, SpriteSheetError> {
+    // Web assets are fetched asynchronously, waiting for them would never finish
+    if cfg!(target_arch = "wasm32") {
+        return Err(
+            SpriteSheetError::Io {
+                path: data_path.to_path_buf(),
+                source: std::io::Error::new(std::io::ErrorKind::Unsupported, "data files can only be loaded through the AssetServer on the web"),
+            }
+        );
+    }
+
+    return futures_lite::future::block_on(asset_server.asset_io().load_path(data_path))
+        .map_err(|error| SpriteSheetError::from_asset_io(data_path, error));
+}
+
+/// Reads the content of a text data file, see [`read_data_bytes`].
+pub(crate) fn read_data_file(
+    asset_server: &AssetServer,
+    data_path: &Path,
+) -> Result<String, SpriteSheetError> {
+    return String::from_utf8(read_data_bytes(asset_server, data_path)?).map_err(|error| {
+        SpriteSheetError::Io {
+            path: data_path.to_path_buf(),
+            source: std::io::Error::new(std::io::ErrorKind::InvalidData, error),
+        }
+    });
+}
+
+// Joins the frames of the pages, setting the page of every frame
+pub(crate) fn join_pages(
+    pages: &[SpriteSheetPage],
+) -> Vec<SpriteSheetFrame> {
+    return pages.iter().enumerate().flat_map(|(page, sprite_sheet_page)| {
+        return sprite_sheet_page.frames.iter().map(move |frame| SpriteSheetFrame { page, ..frame.clone() });
+    }).collect();
+}
+
+/// Creates an `AnimationLibrary` by loading the image of every page through the `AssetServer`
+/// and adding the frames of the page to a new `TextureAtlas`.
+pub(crate) fn create_animation_library(
+    data_path: &Path,
+    pages: Vec<SpriteSheetPage>,
+    texture_atlases: &mut Assets<TextureAtlas>,
+    asset_server: &AssetServer,
+) -> Result<AnimationLibrary, SpriteSheetError> {
+    let frames = join_pages(&pages);
+
+    check_frames(data_path, &frames)?;
+
+    let mut texture_atlas_handles = Vec::new();
+
+    for page in pages.iter() {
+        let image_path = resolve_image_path(data_path, page.image_path.as_deref());
+
+        if !asset_server.asset_io().is_file(&image_path) {
+            return Err(SpriteSheetError::MissingImage { path: data_path.to_path_buf(), image_path });
+        }
+
+        let texture = asset_server.load(image_path);
+
+        texture_atlas_handles.push(texture_atlases.add(create_texture_atlas(texture, &page.frames)));
+    }
+
+    // The data file isn't loaded as a SpriteSheet, it would be parsed a second time into texture
+    // atlases the library doesn't use, so libraries created synchronously aren't reloaded
+    return Ok(AnimationLibrary::from_frames(texture_atlas_handles, &frames));
+}
+
+/// Parses the pages of a data file, the data format is detected from its extension.
+///
+/// Aseprite files aren't parsed here, their image is composited from the file instead of being
+/// loaded from a path.
+pub(crate) fn parse_data_file(
+    data_path: &Path,
+    data: &[u8],
+) -> Result<Vec<SpriteSheetPage>, SpriteSheetError> {
+    // Property lists may be binary, the other formats are text
+    let content = || {
+        return std::str::from_utf8(data).map_err(|error| {
+            SpriteSheetError::Io {
+                path: data_path.to_path_buf(),
+                source: std::io::Error::new(std::io::ErrorKind::InvalidData, error),
+            }
+        });
+    };
+
+    return match data_path.extension().and_then(|extension| extension.to_str()) {
+        Some("xml") => sparrow::parse(data_path, content()?).map(SpriteSheetPage::single),
+        Some("json") | Some("eas") => json::parse_layout(data_path, content()?),
+        Some("plist") => cocos2d::parse(data_path, data).map(SpriteSheetPage::single),
+        Some("atlas") => libgdx::parse(data_path, content()?),
+        _ => Err(
+            SpriteSheetError::Parse {
+                path: data_path.to_path_buf(),
+                line: None,
+                column: None,
+                message: "unsupported data format".to_string(),
+            }
+        ),
+    };
+}
+
+// The assets of the pages of a sprite sheet, added to a load context
+#[derive(Debug, Default)]
+pub(crate) struct PageAssets {
+    pub(crate) textures: Vec<Handle<Image>>,
+    pub(crate) texture_atlases: Vec<Handle<TextureAtlas>>,
+    pub(crate) image_paths: Vec<AssetPath<'static>>,
+}
+
+/// Adds the `TextureAtlas` of every page as a labeled asset of the load context, `atlas` for
+/// the first page and `atlas1`, `atlas2`, ... for the following ones.
+///
+/// # Returns
+///
+/// The handles to the images and the texture atlases of the pages, and the paths of the images,
+/// which have to be added as dependencies of the default asset.
+pub(crate) fn set_texture_atlas_assets(
+    load_context: &mut LoadContext,
+    data_path: &Path,
+    pages: &[SpriteSheetPage],
+) -> Result<PageAssets, SpriteSheetError> {
+    check_frames(data_path, &join_pages(pages))?;
+
+    let mut page_assets = PageAssets::default();
+
+    for (index, page) in pages.iter().enumerate() {
+        let image_path = resolve_image_path(data_path, page.image_path.as_deref());
+
+        if !load_context.asset_io().is_file(&image_path) {
+            return Err(SpriteSheetError::MissingImage { path: data_path.to_path_buf(), image_path });
+        }
+
+        let image_path = AssetPath::new(image_path, None);
+
+        let texture: Handle<Image> = load_context.get_handle(image_path.clone());
+
+        let label = if index == 0 { "atlas".to_string() } else { format!("atlas{index}") };
+
+        let texture_atlas = load_context.set_labeled_asset(
+            &label,
+            LoadedAsset::new(create_texture_atlas(texture.clone(), &page.frames)),
+        );
+
+        page_assets.textures.push(texture);
+        page_assets.texture_atlases.push(texture_atlas);
+        page_assets.image_paths.push(image_path);
+    }
+
+    return Ok(page_assets);
+}
+
+/// Sets the `SpriteSheet` as the default asset of the load context.
+///
+/// The images are loaded as dependencies and the texture atlases are added as labeled assets,
+/// see `set_texture_atlas_assets`.
+pub(crate) fn set_sprite_sheet_asset(
+    load_context: &mut LoadContext,
+    pages: Vec<SpriteSheetPage>,
+    tags: Vec<FrameTag>,
+    slices: Vec<Slice>,
+) -> Result<(), SpriteSheetError> {
+    let data_path = load_context.path().to_path_buf();
+
+    let page_assets = set_texture_atlas_assets(load_context, &data_path, &pages)?;
+
+    load_context.set_default_asset(
+        LoadedAsset::new(
+            SpriteSheet {
+                texture: page_assets.textures[0].clone(),
+                texture_atlas: page_assets.texture_atlases[0].clone(),
+                pages: page_assets.texture_atlases,
+                frames: join_pages(&pages),
+                tags,
+                slices,
+            }
+        ).with_dependencies(page_assets.image_paths)
+    );
+
+    return Ok(());
+}
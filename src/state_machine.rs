@@ -0,0 +1,392 @@
+// Import necessary modules and crates
+use std::collections::{HashMap, HashSet};
+
+use bevy::asset::{AssetLoader, LoadContext, LoadedAsset};
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use bevy::utils::BoxedFuture;
+
+use serde::Deserialize;
+
+use crate::AnimatedSprite;
+use crate::AnimationLibrary;
+use crate::SpriteSheetError;
+
+/// Condition that has to be met for an `AnimationTransition` to be taken.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub enum AnimationCondition {
+    /// The bool parameter with the name has the value. Parameters that weren't set are `false`.
+    Bool(String, bool),
+    /// The float parameter with the name is greater than the value. Parameters that weren't
+    /// set are `0.0`.
+    Greater(String, f32),
+    /// The float parameter with the name is less than the value. Parameters that weren't
+    /// set are `0.0`.
+    Less(String, f32),
+    /// The trigger with the name is set. Triggers are reset by the transition that uses them.
+    Trigger(String),
+    /// The animation of the current state has finished playing.
+    Finished,
+    /// The animation of the current state has played for the number of passes, counting the
+    /// completed passes, so `1.0` is the end of the first pass and `2.5` the middle of the
+    /// third one, even if the animation loops. Every direction of a ping-pong animation is a pass.
+    ExitTime(f32),
+}
+
+/// Struct representing a state of an `AnimationGraph`.
+///
+/// # Fields
+///
+/// - `name`: The name of the state.
+/// - `animation`: The name of the animation played while the state is active.
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct AnimationState {
+    /// The name of the state.
+    pub name: String,
+    /// The name of the animation played while the state is active.
+    pub animation: String,
+}
+
+/// Struct representing a transition between two states of an `AnimationGraph`.
+///
+/// # Fields
+///
+/// - `from`: The name of the state the transition starts from, `*` for any other state.
+/// - `to`: The name of the state the transition leads to.
+/// - `conditions`: The conditions that all have to be met for the transition to be taken.
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct AnimationTransition {
+    /// The name of the state the transition starts from, `*` for any other state.
+    pub from: String,
+    /// The name of the state the transition leads to.
+    pub to: String,
+    /// The conditions that all have to be met for the transition to be taken.
+    #[serde(default)]
+    pub conditions: Vec<AnimationCondition>,
+}
+
+/// Asset describing the states and transitions of an `AnimationStateMachine`.
+///
+/// It can be built in code or loaded from a `.graph.ron` or `.graph.json` file:
+///
+/// ```ron
+/// (
+///     initial_state: "idle",
+///     states: [
+///         (name: "idle", animation: "idle"),
+///         (name: "run", animation: "run"),
+///         (name: "attack", animation: "attack"),
+///     ],
+///     transitions: [
+///         (from: "idle", to: "run", conditions: [Greater("speed", 0.1)]),
+///         (from: "run", to: "idle", conditions: [Less("speed", 0.1)]),
+///         (from: "*", to: "attack", conditions: [Trigger("attack")]),
+///         (from: "attack", to: "idle", conditions: [Finished]),
+///     ],
+/// )
+/// ```
+///
+/// Transitions are checked in order, the first one whose conditions are met is taken.
+///
+/// # Fields
+///
+/// - `initial_state`: The name of the state the state machine starts in.
+/// - `states`: The states of the graph.
+/// - `transitions`: The transitions between the states.
+#[derive(Debug, Default, Clone, Deserialize, TypeUuid)]
+#[uuid = "3f6e2a1d-8c4b-4e7a-9d5f-1b2c3d4e5f60"]
+pub struct AnimationGraph {
+    /// The name of the state the state machine starts in.
+    pub initial_state: String,
+    /// The states of the graph.
+    #[serde(default)]
+    pub states: Vec<AnimationState>,
+    /// The transitions between the states.
+    #[serde(default)]
+    pub transitions: Vec<AnimationTransition>,
+}
+
+impl AnimationGraph {
+    /// Creates an empty `AnimationGraph`.
+    ///
+    /// # Parameters
+    ///
+    /// - `initial_state`: The name of the state the state machine starts in.
+    pub fn new(
+        initial_state: &str,
+    ) -> Self {
+        return AnimationGraph {
+            initial_state: initial_state.to_string(),
+            ..default()
+        };
+    }
+
+    /// Adds a state to the graph, replacing an existing state with the same name.
+    ///
+    /// # Parameters
+    ///
+    /// - `name`: The name of the state.
+    /// - `animation`: The name of the animation played while the state is active.
+    ///
+    /// # Returns
+    ///
+    /// The `AnimationGraph`, so more states and transitions can be added.
+    pub fn add_state(
+        &mut self,
+        name: &str,
+        animation: &str,
+    ) -> &mut Self {
+        self.states.retain(|state| state.name != name);
+        self.states.push(
+            AnimationState {
+                name: name.to_string(),
+                animation: animation.to_string(),
+            }
+        );
+
+        return self;
+    }
+
+    /// Adds a transition to the graph.
+    ///
+    /// # Parameters
+    ///
+    /// - `from`: The name of the state the transition starts from, `*` for any other state.
+    /// - `to`: The name of the state the transition leads to.
+    /// - `conditions`: The conditions that all have to be met for the transition to be taken.
+    ///
+    /// # Returns
+    ///
+    /// The `AnimationGraph`, so more states and transitions can be added.
+    pub fn add_transition(
+        &mut self,
+        from: &str,
+        to: &str,
+        conditions: Vec<AnimationCondition>,
+    ) -> &mut Self {
+        self.transitions.push(
+            AnimationTransition {
+                from: from.to_string(),
+                to: to.to_string(),
+                conditions,
+            }
+        );
+
+        return self;
+    }
+
+    /// Retrieves a state of the graph by name.
+    pub fn state(
+        &self,
+        name: &str,
+    ) -> Option<&AnimationState> {
+        return self.states.iter().find(|state| state.name == name);
+    }
+}
+
+/// Component playing the animations of an `AnimatedSprite` on the same entity according to
+/// an `AnimationGraph`.
+///
+/// Gameplay code sets the parameters, the transitions are evaluated by `update_animations`
+/// right before the frame of the `AnimatedSprite` is updated, so the animation of a new
+/// state starts in the same update.
+///
+/// # Fields
+///
+/// - `graph`: The handle to the `AnimationGraph` containing the states and transitions.
+#[derive(Debug, Default, Component)]
+pub struct AnimationStateMachine {
+    /// The handle to the `AnimationGraph` containing the states and transitions.
+    pub graph: Handle<AnimationGraph>,
+
+    current_state: Option<String>,
+    bools: HashMap<String, bool>,
+    floats: HashMap<String, f32>,
+    triggers: HashSet<String>,
+}
+
+impl AnimationStateMachine {
+    /// Creates an `AnimationStateMachine` that starts in the initial state of the graph.
+    ///
+    /// # Parameters
+    ///
+    /// - `graph`: The handle to the `AnimationGraph` containing the states and transitions.
+    pub fn new(
+        graph: Handle<AnimationGraph>,
+    ) -> Self {
+        return AnimationStateMachine {
+            graph,
+            ..default()
+        };
+    }
+
+    /// Sets a bool parameter.
+    pub fn set_bool(
+        &mut self,
+        name: &str,
+        value: bool,
+    ) {
+        self.bools.insert(name.to_string(), value);
+    }
+
+    /// Retrieves a bool parameter, `false` if it wasn't set.
+    pub fn get_bool(
+        &self,
+        name: &str,
+    ) -> bool {
+        return self.bools.get(name).copied().unwrap_or(false);
+    }
+
+    /// Sets a float parameter.
+    pub fn set_float(
+        &mut self,
+        name: &str,
+        value: f32,
+    ) {
+        self.floats.insert(name.to_string(), value);
+    }
+
+    /// Retrieves a float parameter, `0.0` if it wasn't set.
+    pub fn get_float(
+        &self,
+        name: &str,
+    ) -> f32 {
+        return self.floats.get(name).copied().unwrap_or(0f32);
+    }
+
+    /// Sets a trigger, which stays set until a transition uses it.
+    pub fn set_trigger(
+        &mut self,
+        name: &str,
+    ) {
+        self.triggers.insert(name.to_string());
+    }
+
+    /// Resets a trigger that wasn't used by a transition yet.
+    pub fn reset_trigger(
+        &mut self,
+        name: &str,
+    ) {
+        self.triggers.remove(name);
+    }
+
+    /// Retrieves the name of the current state.
+    ///
+    /// # Returns
+    ///
+    /// The name of the current state, or `None` if the state machine hasn't started yet.
+    pub fn current_state(
+        &self
+    ) -> Option<&str> {
+        return self.current_state.as_deref();
+    }
+
+    // Checks whether a condition of a transition is met
+    fn condition_is_met(
+        &self,
+        condition: &AnimationCondition,
+        animated_sprite: &AnimatedSprite,
+        library: &AnimationLibrary,
+    ) -> bool {
+        return match condition {
+            AnimationCondition::Bool(name, value) => self.get_bool(name) == *value,
+            AnimationCondition::Greater(name, value) => self.get_float(name) > *value,
+            AnimationCondition::Less(name, value) => self.get_float(name) < *value,
+            AnimationCondition::Trigger(name) => self.triggers.contains(name),
+            AnimationCondition::Finished => animated_sprite.animation_is_finished,
+            AnimationCondition::ExitTime(passes) => animated_sprite.played_passes(library) >= *passes,
+        };
+    }
+
+    // Makes a state the current state and plays its animation
+    fn enter_state(
+        &mut self,
+        graph: &AnimationGraph,
+        state_name: &str,
+        animated_sprite: &mut AnimatedSprite,
+    ) {
+        self.current_state = Some(state_name.to_string());
+
+        match graph.state(state_name) {
+            Some(state) => animated_sprite.play_animation(&state.animation, true),
+            None => println!("\x1b[38;5;196mState ({state_name}) doesn't exist\x1b[0;0;0m"),
+        }
+    }
+
+    /// Enters the initial state or takes the first transition whose conditions are met.
+    ///
+    /// # Parameters
+    ///
+    /// - `graph`: Reference to the graph containing the states and transitions.
+    /// - `animated_sprite`: Reference to the sprite playing the animations of the states.
+    /// - `library`: Reference to the library containing the animations.
+    pub(crate) fn update(
+        &mut self,
+        graph: &AnimationGraph,
+        animated_sprite: &mut AnimatedSprite,
+        library: &AnimationLibrary,
+    ) {
+        let Some(current_state) = self.current_state.as_deref() else {
+            self.enter_state(graph, &graph.initial_state, animated_sprite);
+            return;
+        };
+
+        let transition = graph.transitions.iter().find(|transition| {
+            // Transitions from any state don't restart the state they lead to
+            let starts_here = transition.from == current_state
+                || (transition.from == "*" && transition.to != current_state);
+
+            return starts_here && transition.conditions.iter().all(|condition| {
+                self.condition_is_met(condition, animated_sprite, library)
+            });
+        });
+
+        let Some(transition) = transition else {
+            return;
+        };
+
+        // Triggers are used up by the transition
+        for condition in transition.conditions.iter() {
+            if let AnimationCondition::Trigger(name) = condition {
+                self.triggers.remove(name);
+            }
+        }
+
+        self.enter_state(graph, &transition.to, animated_sprite);
+    }
+}
+
+/// Asset loader for `.graph.ron` and `.graph.json` files containing an `AnimationGraph`.
+#[derive(Debug, Default)]
+pub struct AnimationGraphLoader;
+
+impl AssetLoader for AnimationGraphLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let content = std::str::from_utf8(bytes)?;
+
+            // Remove the BOM if present (UTF-8 BOM is 0xEF, 0xBB, 0xBF)
+            let content = content.trim_start_matches('\u{FEFF}');
+
+            let path = load_context.path();
+
+            let graph: AnimationGraph = if path.extension().is_some_and(|extension| extension == "ron") {
+                ron::from_str(content).map_err(|error| SpriteSheetError::from_ron(path, error))?
+            } else {
+                serde_json::from_str(content).map_err(|error| SpriteSheetError::from_json(path, error))?
+            };
+
+            load_context.set_default_asset(LoadedAsset::new(graph));
+
+            return Ok(());
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        return &["graph.ron", "graph.json"];
+    }
+}
@@ -0,0 +1,51 @@
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::asset::AssetPath;
+
+use bevy_ss_anim::*;
+
+#[test]
+fn references_binary_property_lists() {
+    let mut app = common::app();
+    let handle: Handle<AnimationLibrary> = common::load(&mut app, "binary.anim.ron");
+
+    let library = app.world.resource::<Assets<AnimationLibrary>>().get(&handle).expect("the definition is loaded");
+
+    assert_eq!(library.animation("run").unwrap().indices, vec![0, 1]);
+}
+
+#[test]
+fn references_aseprite_files() {
+    let mut app = common::app();
+    let handle: Handle<AnimationLibrary> = common::load(&mut app, "composite.anim.ron");
+
+    let library = app.world.resource::<Assets<AnimationLibrary>>().get(&handle).expect("the definition is loaded");
+
+    // The tags of the file are kept next to the animations of the definition
+    assert_eq!(library.animation("second").unwrap().indices, vec![1]);
+    assert_eq!(library.animation("walk").unwrap().loop_mode, LoopMode::PingPongPasses(3));
+
+    // The library uses the texture atlas composited by the AsepriteLoader
+    let atlas_path = AssetPath::new_ref("composite.aseprite".as_ref(), Some("atlas"));
+    assert_eq!(library.texture_atlas.id(), atlas_path.into());
+
+    let texture_atlas = library.texture_atlas.clone();
+
+    for _ in 0..200 {
+        if app.world.resource::<Assets<TextureAtlas>>().contains(&texture_atlas) {
+            break;
+        }
+
+        app.update();
+        std::thread::sleep(Duration::from_millis(5));
+    }
+
+    let texture_atlas = app.world.resource::<Assets<TextureAtlas>>().get(&texture_atlas).expect("the atlas is loaded");
+
+    assert_eq!(texture_atlas.textures[1], Rect::new(4.0, 0.0, 8.0, 4.0));
+}
@@ -0,0 +1,92 @@
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use bevy::prelude::*;
+use bevy::asset::LoadState;
+
+use bevy_ss_anim::*;
+
+// Returns the pixel of the image at the given position
+fn pixel(
+    image: &Image,
+    x: usize,
+    y: usize,
+) -> [u8; 4] {
+    let index = (y * image.texture_descriptor.size.width as usize + x) * 4;
+    return image.data[index..index + 4].try_into().unwrap();
+}
+
+#[test]
+fn composites_cels_with_their_position_and_opacity() {
+    let mut app = common::app();
+    let handle: Handle<SpriteSheet> = common::load(&mut app, "composite.aseprite");
+
+    let sprite_sheet = app.world.resource::<Assets<SpriteSheet>>().get(&handle).expect("the file is loaded");
+    let image = app.world.resource::<Assets<Image>>().get(&sprite_sheet.texture).unwrap();
+
+    let red = [255, 0, 0, 255];
+
+    // The frames are laid out in a grid of 4x4 frames
+    assert_eq!(sprite_sheet.frames.len(), 2);
+    assert_eq!(sprite_sheet.frames[0].name, "composite 0.aseprite");
+    assert_eq!(sprite_sheet.frames[1].rect, Rect::new(4.0, 0.0, 8.0, 4.0));
+
+    // A blue cel at (1, 2) on a layer with half opacity
+    assert_eq!(pixel(image, 0, 2), red);
+    assert_eq!(pixel(image, 1, 2), [127, 0, 128, 255]);
+    assert_eq!(pixel(image, 2, 2), [127, 0, 128, 255]);
+    assert_eq!(pixel(image, 3, 2), red);
+
+    // The background is linked to the first frame, the green cel at (-1, 0) is clipped
+    assert_eq!(pixel(image, 4, 0), [127, 128, 0, 255]);
+    assert_eq!(pixel(image, 4, 1), [127, 128, 0, 255]);
+    assert_eq!(pixel(image, 5, 0), red);
+    assert_eq!(pixel(image, 4, 2), red);
+}
+
+#[test]
+fn reads_durations_and_tags() {
+    let mut app = common::app();
+    let handle: Handle<SpriteSheet> = common::load(&mut app, "composite.aseprite");
+
+    let sprite_sheet = app.world.resource::<Assets<SpriteSheet>>().get(&handle).expect("the file is loaded");
+
+    assert_eq!(sprite_sheet.frames[0].duration, Some(0.1));
+    assert_eq!(sprite_sheet.frames[1].duration, Some(0.2));
+
+    assert_eq!(sprite_sheet.tags.len(), 1);
+
+    let tag = &sprite_sheet.tags[0];
+    assert_eq!(tag.name, "walk");
+    assert_eq!((tag.from, tag.to), (0, 1));
+    assert_eq!(tag.direction, TagDirection::PingPong);
+    assert_eq!(tag.repeat, Some(3));
+
+    let library = AnimationLibrary::from_sprite_sheet(sprite_sheet);
+    let animation = library.animation("walk").unwrap();
+
+    assert_eq!(animation.indices, vec![0, 1]);
+    assert_eq!(animation.loop_mode, LoopMode::PingPongPasses(3));
+    assert_eq!(animation.durations, vec![0.1, 0.2]);
+}
+
+#[test]
+fn rejects_invalid_palettes() {
+    let mut app = common::app();
+    let handle: Handle<SpriteSheet> = common::load(&mut app, "bad_palette.aseprite");
+
+    assert_eq!(app.world.resource::<AssetServer>().get_load_state(&handle), LoadState::Failed);
+}
+
+#[test]
+fn skips_linked_cels_that_link_to_each_other() {
+    let mut app = common::app();
+    let handle: Handle<SpriteSheet> = common::load(&mut app, "linked_cycle.aseprite");
+
+    let sprite_sheet = app.world.resource::<Assets<SpriteSheet>>().get(&handle).expect("the file is loaded");
+    let image = app.world.resource::<Assets<Image>>().get(&sprite_sheet.texture).unwrap();
+
+    assert_eq!(sprite_sheet.frames.len(), 2);
+    assert!(image.data.iter().all(|byte| *byte == 0));
+}
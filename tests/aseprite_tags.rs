@@ -0,0 +1,67 @@
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use bevy_ss_anim::*;
+
+// Plays a ping-pong tag over three frames of 100 milliseconds and returns the frames it shows
+fn play_ping_pong(
+    repeat: Option<u32>,
+) -> Vec<usize> {
+    let mut app = common::app();
+
+    let sprite_sheet = common::sprite_sheet(
+        &[Some(0.1); 3],
+        vec![FrameTag {
+            name: "tag".to_string(),
+            from: 0,
+            to: 2,
+            direction: TagDirection::PingPong,
+            repeat,
+        }],
+    );
+
+    let entity = common::spawn(&mut app, AnimationLibrary::from_sprite_sheet(&sprite_sheet), "tag");
+
+    common::update(&mut app, 0);
+
+    let mut shown = vec![common::shown_frame(&app, entity)];
+
+    for _ in 1..20 {
+        common::update(&mut app, 100);
+
+        let index = common::shown_frame(&app, entity);
+
+        if shown.last() != Some(&index) {
+            shown.push(index);
+        }
+
+        if common::animated_sprite(&app, entity).animation_is_finished {
+            break;
+        }
+    }
+
+    assert!(common::animated_sprite(&app, entity).animation_is_finished || repeat.is_none());
+
+    return shown;
+}
+
+#[test]
+fn ping_pong_repeat_once_plays_forward() {
+    assert_eq!(play_ping_pong(Some(1)), vec![0, 1, 2]);
+}
+
+#[test]
+fn ping_pong_repeat_twice_plays_forward_and_back() {
+    assert_eq!(play_ping_pong(Some(2)), vec![0, 1, 2, 1, 0]);
+}
+
+#[test]
+fn ping_pong_repeat_three_times_ends_forward() {
+    assert_eq!(play_ping_pong(Some(3)), vec![0, 1, 2, 1, 0, 1, 2]);
+}
+
+#[test]
+fn ping_pong_without_repeat_loops() {
+    assert_eq!(play_ping_pong(None)[..7], [0, 1, 2, 1, 0, 1, 2]);
+}
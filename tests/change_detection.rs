@@ -0,0 +1,52 @@
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use bevy::prelude::*;
+
+use bevy_ss_anim::*;
+
+// The number of updates the sprite and its texture atlas handle were changed in
+#[derive(Resource, Default)]
+struct Changes {
+    sprites: usize,
+    texture_atlases: usize,
+}
+
+fn count_changes(
+    sprites: Query<(), Changed<TextureAtlasSprite>>,
+    texture_atlases: Query<(), Changed<Handle<TextureAtlas>>>,
+    mut changes: ResMut<Changes>,
+) {
+    changes.sprites += sprites.iter().count();
+    changes.texture_atlases += texture_atlases.iter().count();
+}
+
+#[test]
+fn held_frames_do_not_change_the_sprite() {
+    let mut app = common::app();
+
+    app.init_resource::<Changes>()
+        .add_system_to_stage(CoreStage::PostUpdate, count_changes);
+
+    // Two frames of 500 milliseconds
+    let mut library = AnimationLibrary::from_sprite_sheet(&common::sprite_sheet(&[Some(0.5); 2], Vec::new()));
+    library.add_animation_by_range("loop", 0, 1, 2f32, LoopMode::Loop, Vec2::ZERO);
+
+    common::spawn(&mut app, library, "loop");
+
+    // Spawning and showing the first frame change the sprite
+    common::update(&mut app, 0);
+
+    *app.world.resource_mut::<Changes>() = Changes::default();
+
+    // The first frame is held for 4 updates, the 5th one shows the second frame
+    for _ in 1..=5 {
+        common::update(&mut app, 100);
+    }
+
+    let changes = app.world.resource::<Changes>();
+
+    assert_eq!(changes.sprites, 1);
+    assert_eq!(changes.texture_atlases, 0);
+}
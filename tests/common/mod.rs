@@ -0,0 +1,149 @@
+// Helpers shared by the integration tests, not every test uses all of them
+#![allow(dead_code, clippy::needless_return)]
+
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+use bevy::asset::{Asset, AssetPlugin, LoadState};
+use bevy::core::CorePlugin;
+use bevy::ecs::event::Event;
+
+use bevy_ss_anim::*;
+
+// Creates an app with the SpriteSheetAnimationPlugin, loading assets from tests/fixtures
+pub fn app() -> App {
+    let mut app = App::new();
+
+    app.add_plugin(CorePlugin::default())
+        .add_plugin(AssetPlugin {
+            asset_folder: concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures").to_string(),
+            watch_for_changes: false,
+        })
+        .add_asset::<Image>()
+        .add_asset::<TextureAtlas>()
+        .add_plugin(SpriteSheetAnimationPlugin::default())
+        .init_resource::<Time>();
+
+    return app;
+}
+
+// Loads an asset of tests/fixtures, updating the app until it's loaded or failed
+pub fn load<T: Asset>(
+    app: &mut App,
+    path: &str,
+) -> Handle<T> {
+    let handle: Handle<T> = app.world.resource::<AssetServer>().load(path);
+
+    for _ in 0..200 {
+        app.update();
+
+        if let LoadState::Loaded | LoadState::Failed = app.world.resource::<AssetServer>().get_load_state(&handle) {
+            break;
+        }
+
+        std::thread::sleep(Duration::from_millis(5));
+    }
+
+    return handle;
+}
+
+// Creates a sprite sheet with a frame of 2x2 pixels for every duration, laid out in a row
+pub fn sprite_sheet(
+    durations: &[Option<f32>],
+    tags: Vec<FrameTag>,
+) -> SpriteSheet {
+    let frames = durations.iter().enumerate().map(|(index, duration)| SpriteSheetFrame {
+        name: format!("frame{index}"),
+        rect: Rect::new(index as f32 * 2f32, 0f32, index as f32 * 2f32 + 2f32, 2f32),
+        source_size: Vec2::splat(2f32),
+        duration: *duration,
+        ..default()
+    }).collect();
+
+    return SpriteSheet {
+        texture: Handle::default(),
+        texture_atlas: Handle::default(),
+        pages: vec![Handle::default()],
+        frames,
+        tags,
+        slices: Vec::new(),
+    };
+}
+
+// Creates a library of a sprite sheet with a frame for every duration, without animations
+pub fn library(
+    durations: &[Option<f32>],
+) -> AnimationLibrary {
+    return AnimationLibrary::from_sprite_sheet(&sprite_sheet(durations, Vec::new()));
+}
+
+// Adds the library and spawns a sprite playing the animation
+pub fn spawn(
+    app: &mut App,
+    library: AnimationLibrary,
+    animation: &str,
+) -> Entity {
+    let library = app.world.resource_mut::<Assets<AnimationLibrary>>().add(library);
+
+    let mut animated_sprite = AnimatedSprite::default();
+    animated_sprite.library = library;
+    animated_sprite.play_animation(animation, true);
+
+    return app.world.spawn((animated_sprite, TextureAtlasSprite::default(), Handle::<TextureAtlas>::default())).id();
+}
+
+// Advances the time by the milliseconds and updates the app, the first update has no delta
+pub fn update(
+    app: &mut App,
+    milliseconds: u64,
+) {
+    let mut time = app.world.resource_mut::<Time>();
+    let instant = time.last_update().unwrap_or_else(Instant::now) + Duration::from_millis(milliseconds);
+    time.update_with_instant(instant);
+
+    app.update();
+}
+
+// Returns the index of the frame the sprite shows
+pub fn shown_frame(
+    app: &App,
+    entity: Entity,
+) -> usize {
+    return app.world.get::<TextureAtlasSprite>(entity).unwrap().index;
+}
+
+// Returns the animated sprite of the entity
+pub fn animated_sprite(
+    app: &App,
+    entity: Entity,
+) -> &AnimatedSprite {
+    return app.world.get::<AnimatedSprite>(entity).unwrap();
+}
+
+// Returns the animated sprite of the entity to change it
+pub fn animated_sprite_mut(
+    app: &mut App,
+    entity: Entity,
+) -> Mut<'_, AnimatedSprite> {
+    return app.world.get_mut::<AnimatedSprite>(entity).unwrap();
+}
+
+// Updates the app once per step and returns the frame the sprite shows after every update
+pub fn play(
+    app: &mut App,
+    entity: Entity,
+    steps: usize,
+    milliseconds: u64,
+) -> Vec<usize> {
+    return (0..steps).map(|_| {
+        update(app, milliseconds);
+        return shown_frame(app, entity);
+    }).collect();
+}
+
+// Returns the events of the type sent in the last update
+pub fn events<T: Event + Clone>(
+    app: &App,
+) -> Vec<T> {
+    return app.world.resource::<Events<T>>().iter_current_update_events().cloned().collect();
+}
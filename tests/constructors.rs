@@ -0,0 +1,39 @@
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use bevy::prelude::*;
+
+use bevy_ss_anim::*;
+
+#[test]
+fn from_cocos2d_reads_binary_property_lists() {
+    let mut app = common::app();
+
+    let library = app.world.resource_scope(|world, mut texture_atlases: Mut<Assets<TextureAtlas>>| {
+        return AnimationLibrary::from_cocos2d("binary", &mut texture_atlases, world.resource::<AssetServer>());
+    });
+
+    let mut library = library.expect("the binary property list is read");
+
+    assert!(library.add_animation_by_prefix("run", "run", 10f32, LoopMode::Loop, Vec2::ZERO).is_some());
+    assert_eq!(library.animation("run").unwrap().indices, vec![0, 1]);
+}
+
+#[test]
+fn bundles_add_their_library() {
+    let mut app = common::app();
+
+    let bundle = app.world.resource_scope(|world, mut texture_atlases: Mut<Assets<TextureAtlas>>| {
+        return world.resource_scope(|world, mut libraries: Mut<Assets<AnimationLibrary>>| {
+            return AnimatedSpriteBundle::from_sparrow("sparrow", &mut texture_atlases, world.resource::<AssetServer>(), &mut libraries);
+        });
+    });
+
+    let bundle = bundle.expect("the sparrow data file is read");
+
+    let libraries = app.world.resource::<Assets<AnimationLibrary>>();
+    let library = libraries.get(&bundle.animated_sprite.library).expect("the library is added");
+
+    assert_eq!(bundle.sprite_sheet_bundle.texture_atlas, library.texture_atlas);
+}
@@ -0,0 +1,159 @@
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use bevy::prelude::*;
+
+use bevy_ss_anim::*;
+
+// Plays an animation over four frames of 250 milliseconds that loops from a frame
+//
+// Returns the shown frames, up to ten, and whether the animation has finished
+fn play(
+    loop_mode: LoopMode,
+    loop_start: usize,
+) -> (Vec<usize>, bool) {
+    let mut app = common::app();
+
+    let mut library = common::library(&[Some(0.25); 4]);
+    library.add_animation_by_range("walk", 0, 3, 4f32, loop_mode, Vec2::ZERO).unwrap().loop_start = loop_start;
+
+    let entity = common::spawn(&mut app, library, "walk");
+
+    common::update(&mut app, 0);
+
+    let mut shown = vec![common::shown_frame(&app, entity)];
+
+    while shown.len() < 10 {
+        common::update(&mut app, 250);
+
+        // The animation finishes on the update after its last frame
+        if common::animated_sprite(&app, entity).animation_is_finished {
+            return (shown, true);
+        }
+
+        shown.push(common::shown_frame(&app, entity));
+    }
+
+    return (shown, false);
+}
+
+#[test]
+fn once_stops_at_the_last_frame() {
+    assert_eq!(play(LoopMode::Once, 1), (vec![0, 1, 2, 3], true));
+}
+
+#[test]
+fn loop_restarts_at_the_loop_start() {
+    assert_eq!(play(LoopMode::Loop, 0), (vec![0, 1, 2, 3, 0, 1, 2, 3, 0, 1], false));
+    assert_eq!(play(LoopMode::Loop, 2), (vec![0, 1, 2, 3, 2, 3, 2, 3, 2, 3], false));
+}
+
+#[test]
+fn loop_times_counts_every_pass() {
+    assert_eq!(play(LoopMode::LoopTimes(2), 0), (vec![0, 1, 2, 3, 0, 1, 2, 3], true));
+    assert_eq!(play(LoopMode::LoopTimes(2), 1), (vec![0, 1, 2, 3, 1, 2, 3], true));
+}
+
+#[test]
+fn ping_pong_turns_around_at_the_loop_start() {
+    assert_eq!(play(LoopMode::PingPong, 0), (vec![0, 1, 2, 3, 2, 1, 0, 1, 2, 3], false));
+    assert_eq!(play(LoopMode::PingPong, 1), (vec![0, 1, 2, 3, 2, 1, 2, 3, 2, 1], false));
+}
+
+#[test]
+fn ping_pong_times_counts_back_and_forth_cycles() {
+    assert_eq!(play(LoopMode::PingPongTimes(1), 0), (vec![0, 1, 2, 3, 2, 1, 0], true));
+    assert_eq!(play(LoopMode::PingPongTimes(1), 1), (vec![0, 1, 2, 3, 2, 1], true));
+}
+
+#[test]
+fn ping_pong_passes_counts_single_directions() {
+    assert_eq!(play(LoopMode::PingPongPasses(3), 1), (vec![0, 1, 2, 3, 2, 1, 2, 3], true));
+}
+
+#[test]
+fn loop_starts_after_the_last_frame_are_clamped() {
+    assert_eq!(play(LoopMode::Loop, 10), (vec![0, 1, 2, 3, 3, 3, 3, 3, 3, 3], false));
+}
+
+#[test]
+fn every_pass_after_the_first_sends_a_looped_event() {
+    let mut app = common::app();
+
+    let mut library = common::library(&[Some(0.25); 4]);
+    library.add_animation_by_range("walk", 0, 3, 4f32, LoopMode::PingPong, Vec2::ZERO).unwrap().loop_start = 1;
+
+    let entity = common::spawn(&mut app, library, "walk");
+    common::update(&mut app, 0);
+
+    // Turns around at the frames 3, 1 and 3
+    let mut looped = 0;
+
+    for _ in 0..8 {
+        common::update(&mut app, 250);
+        looped += common::events::<AnimationLooped>(&app).len();
+    }
+
+    assert_eq!(looped, 3);
+    assert_eq!(common::shown_frame(&app, entity), 2);
+}
+
+#[test]
+fn once_and_hide_hides_the_sprite_until_the_next_animation() {
+    let mut app = common::app();
+
+    let mut library = common::library(&[Some(0.25); 4]);
+    library.add_animation_by_range("jump", 0, 1, 4f32, LoopMode::OnceAndHide, Vec2::ZERO);
+    library.add_animation_by_range("idle", 2, 3, 4f32, LoopMode::Loop, Vec2::ZERO);
+
+    let entity = common::spawn(&mut app, library, "jump");
+    app.world.entity_mut(entity).insert(Visibility::VISIBLE);
+
+    common::update(&mut app, 0);
+    common::update(&mut app, 250);
+
+    assert!(app.world.get::<Visibility>(entity).unwrap().is_visible);
+
+    common::update(&mut app, 250);
+
+    assert!(common::animated_sprite(&app, entity).animation_is_finished);
+    assert!(!app.world.get::<Visibility>(entity).unwrap().is_visible);
+
+    common::animated_sprite_mut(&mut app, entity).play_animation("idle", true);
+    common::update(&mut app, 0);
+
+    assert!(app.world.get::<Visibility>(entity).unwrap().is_visible);
+    assert_eq!(common::shown_frame(&app, entity), 2);
+}
+
+#[test]
+fn animations_without_frames_are_stopped() {
+    let mut app = common::app();
+
+    let mut library = AnimationLibrary::from_sprite_sheet(&common::sprite_sheet(&[Some(0.1); 3], Vec::new()));
+    library.add_animation_by_range("walk", 0, 2, 10f32, LoopMode::Loop, Vec2::ZERO);
+
+    let entity = common::spawn(&mut app, library, "walk");
+    let library = common::animated_sprite(&app, entity).library.clone();
+
+    common::update(&mut app, 0);
+    common::update(&mut app, 100);
+
+    let mut libraries = app.world.resource_mut::<Assets<AnimationLibrary>>();
+    libraries.get_mut(&library).unwrap().animation_mut("walk").unwrap().indices.clear();
+
+    // The playing animation is stopped
+    common::update(&mut app, 100);
+    common::update(&mut app, 100);
+
+    assert_eq!(common::animated_sprite(&app, entity).current_animation(), None);
+
+    // And it isn't started again
+    app.world.get_mut::<AnimatedSprite>(entity).unwrap().play_animation("walk", true);
+
+    common::update(&mut app, 100);
+
+    assert_eq!(common::animated_sprite(&app, entity).current_animation(), None);
+    assert_eq!(common::shown_frame(&app, entity), 1);
+}
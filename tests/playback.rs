@@ -0,0 +1,142 @@
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use bevy::prelude::*;
+
+use bevy_ss_anim::*;
+
+// Spawns a sprite playing an animation over four frames of 250 milliseconds
+fn spawn_walking(
+    app: &mut App,
+    loop_mode: LoopMode,
+) -> Entity {
+    return spawn_walking_at_speed(app, loop_mode, 1f32);
+}
+
+// Spawns a sprite playing the animation of spawn_walking at a speed
+fn spawn_walking_at_speed(
+    app: &mut App,
+    loop_mode: LoopMode,
+    speed: f32,
+) -> Entity {
+    let mut library = common::library(&[Some(0.25); 4]);
+    library.add_animation_by_range("walk", 0, 3, 4f32, loop_mode, Vec2::ZERO);
+
+    let entity = common::spawn(app, library, "walk");
+    common::animated_sprite_mut(app, entity).speed = speed;
+    common::update(app, 0);
+
+    return entity;
+}
+
+#[test]
+fn long_updates_advance_several_frames() {
+    let mut app = common::app();
+    let entity = spawn_walking(&mut app, LoopMode::Loop);
+
+    common::update(&mut app, 625);
+
+    assert_eq!(common::shown_frame(&app, entity), 2);
+
+    // Every frame that was passed is reported
+    let frames: Vec<usize> = common::events::<FrameChanged>(&app).iter().map(|event| event.frame).collect();
+    assert_eq!(frames, vec![1, 2]);
+
+    // The remaining 125 milliseconds are carried over
+    common::update(&mut app, 125);
+
+    assert_eq!(common::shown_frame(&app, entity), 3);
+}
+
+#[test]
+fn long_updates_loop_and_finish() {
+    let mut app = common::app();
+    let entity = spawn_walking(&mut app, LoopMode::Loop);
+
+    common::update(&mut app, 1125);
+
+    assert_eq!(common::shown_frame(&app, entity), 0);
+    assert_eq!(common::events::<AnimationLooped>(&app).len(), 1);
+
+    let mut app = common::app();
+    let entity = spawn_walking(&mut app, LoopMode::Once);
+
+    // Much longer than the animation, it stops at its last frame
+    common::update(&mut app, 5000);
+
+    assert_eq!(common::shown_frame(&app, entity), 3);
+    assert!(common::animated_sprite(&app, entity).animation_is_finished);
+    assert_eq!(common::events::<AnimationFinished>(&app).len(), 1);
+}
+
+#[test]
+fn short_updates_add_up() {
+    let mut app = common::app();
+    let entity = spawn_walking(&mut app, LoopMode::Loop);
+
+    assert_eq!(common::play(&mut app, entity, 6, 125), vec![0, 1, 1, 2, 2, 3]);
+}
+
+#[test]
+fn paused_animations_hold_their_frame() {
+    let mut app = common::app();
+    let entity = spawn_walking(&mut app, LoopMode::Loop);
+
+    common::update(&mut app, 375);
+    common::animated_sprite_mut(&mut app, entity).pause();
+
+    assert_eq!(common::play(&mut app, entity, 3, 250), vec![1, 1, 1]);
+
+    // The time of the frame that passed before pausing is kept
+    common::animated_sprite_mut(&mut app, entity).resume();
+
+    assert_eq!(common::play(&mut app, entity, 2, 125), vec![2, 2]);
+}
+
+#[test]
+fn speed_scales_the_frame_durations() {
+    let mut app = common::app();
+    let entity = spawn_walking_at_speed(&mut app, LoopMode::Loop, 2f32);
+
+    assert_eq!(common::play(&mut app, entity, 4, 125), vec![1, 2, 3, 0]);
+
+    // Changing the speed takes effect right away
+    common::animated_sprite_mut(&mut app, entity).speed = 0.5;
+
+    assert_eq!(common::play(&mut app, entity, 4, 250), vec![0, 1, 1, 2]);
+}
+
+#[test]
+fn negative_speed_plays_backwards() {
+    let mut app = common::app();
+    let entity = spawn_walking_at_speed(&mut app, LoopMode::Loop, -1f32);
+
+    // Backwards animations start at their last frame and loop back to it
+    assert_eq!(common::shown_frame(&app, entity), 3);
+    assert_eq!(common::play(&mut app, entity, 5, 250), vec![2, 1, 0, 3, 2]);
+
+    let mut app = common::app();
+    let entity = spawn_walking_at_speed(&mut app, LoopMode::Once, -2f32);
+
+    // And finish at their first frame
+    assert_eq!(common::play(&mut app, entity, 3, 125), vec![2, 1, 0]);
+
+    common::update(&mut app, 125);
+
+    assert_eq!(common::shown_frame(&app, entity), 0);
+    assert!(common::animated_sprite(&app, entity).animation_is_finished);
+}
+
+#[test]
+fn reversing_keeps_the_current_frame() {
+    let mut app = common::app();
+    let entity = spawn_walking(&mut app, LoopMode::Loop);
+
+    common::update(&mut app, 500);
+    assert_eq!(common::shown_frame(&app, entity), 2);
+
+    common::animated_sprite_mut(&mut app, entity).speed = -1f32;
+
+    assert_eq!(common::play(&mut app, entity, 3, 250), vec![1, 0, 3]);
+}
@@ -0,0 +1,108 @@
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use bevy::prelude::*;
+
+use bevy_ss_anim::*;
+
+// Spawns a sprite playing "jump", which plays the first two frames once, "walk" loops over
+// the last two frames, every frame lasts 250 milliseconds
+fn spawn_jumping(
+    app: &mut App
+) -> Entity {
+    let mut library = common::library(&[Some(0.25); 4]);
+    library.add_animation_by_range("jump", 0, 1, 4f32, LoopMode::Once, Vec2::ZERO);
+    library.add_animation_by_range("walk", 2, 3, 4f32, LoopMode::Loop, Vec2::ZERO);
+
+    let entity = common::spawn(app, library, "jump");
+    common::update(app, 0);
+
+    return entity;
+}
+
+// Names of the animations of the events sent in the last update
+fn animations<T: bevy::ecs::event::Event + Clone>(
+    app: &App,
+    animation: impl Fn(&T) -> &str,
+) -> Vec<String> {
+    return common::events::<T>(app).iter().map(|event| animation(event).to_string()).collect();
+}
+
+#[test]
+fn queued_animations_start_with_the_leftover_time() {
+    let mut app = common::app();
+    let entity = spawn_jumping(&mut app);
+
+    common::animated_sprite_mut(&mut app, entity).queue_animation("walk", None);
+
+    // "jump" finishes after 500 milliseconds, the other 125 are spent on "walk"
+    common::update(&mut app, 625);
+
+    assert_eq!(common::shown_frame(&app, entity), 2);
+    assert_eq!(common::animated_sprite(&app, entity).current_animation(), Some("walk"));
+    assert_eq!(animations::<AnimationFinished>(&app, |event| &event.animation), vec!["jump"]);
+    assert_eq!(animations::<AnimationStarted>(&app, |event| &event.animation), vec!["walk"]);
+
+    assert_eq!(common::play(&mut app, entity, 2, 125), vec![3, 3]);
+    assert_eq!(common::animated_sprite(&app, entity).queued_animations().count(), 0);
+}
+
+#[test]
+fn times_override_the_loop_mode() {
+    let mut app = common::app();
+    let entity = spawn_jumping(&mut app);
+
+    let mut animated_sprite = common::animated_sprite_mut(&mut app, entity);
+    animated_sprite.queue_animation("walk", Some(2));
+    animated_sprite.queue_animation("jump", None);
+
+    // "walk" loops twice before "jump" plays again
+    assert_eq!(common::play(&mut app, entity, 8, 250), vec![1, 2, 3, 2, 3, 0, 1, 1]);
+    assert!(common::animated_sprite(&app, entity).animation_is_finished);
+}
+
+#[test]
+fn animations_queued_after_finished_animations_start_right_away() {
+    let mut app = common::app();
+    let entity = spawn_jumping(&mut app);
+
+    common::update(&mut app, 1000);
+    assert!(common::animated_sprite(&app, entity).animation_is_finished);
+
+    common::animated_sprite_mut(&mut app, entity).queue_animation("walk", None);
+    common::update(&mut app, 0);
+
+    assert_eq!(common::shown_frame(&app, entity), 2);
+    assert!(!common::animated_sprite(&app, entity).animation_is_finished);
+}
+
+#[test]
+fn missing_queued_animations_are_skipped() {
+    let mut app = common::app();
+    let entity = spawn_jumping(&mut app);
+
+    let mut animated_sprite = common::animated_sprite_mut(&mut app, entity);
+    animated_sprite.queue_animation("fall", None);
+    animated_sprite.queue_animation("walk", None);
+
+    common::update(&mut app, 500);
+
+    assert_eq!(common::shown_frame(&app, entity), 2);
+    assert_eq!(common::animated_sprite(&app, entity).current_animation(), Some("walk"));
+}
+
+#[test]
+fn playing_an_animation_clears_the_queue() {
+    let mut app = common::app();
+    let entity = spawn_jumping(&mut app);
+
+    let mut animated_sprite = common::animated_sprite_mut(&mut app, entity);
+    animated_sprite.queue_animation("walk", None);
+    animated_sprite.play_animation("jump", true);
+
+    assert_eq!(animated_sprite.queued_animations().count(), 0);
+
+    // "jump" plays from the start and stays finished
+    assert_eq!(common::play(&mut app, entity, 4, 250), vec![0, 1, 1, 1]);
+}
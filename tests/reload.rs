@@ -0,0 +1,111 @@
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use bevy::prelude::*;
+
+use bevy_ss_anim::*;
+
+// Adds a texture atlas with the frames of the sprite sheet and uses it as its only page
+fn add_page(
+    app: &mut App,
+    sprite_sheet: &mut SpriteSheet,
+) -> Handle<TextureAtlas> {
+    let mut texture_atlas = TextureAtlas::new_empty(Handle::default(), Vec2::new(sprite_sheet.frames.len() as f32 * 2f32, 2f32));
+
+    for frame in sprite_sheet.frames.iter() {
+        texture_atlas.add_texture(frame.rect);
+    }
+
+    let texture_atlas = app.world.resource_mut::<Assets<TextureAtlas>>().add(texture_atlas);
+
+    sprite_sheet.texture_atlas = texture_atlas.clone();
+    sprite_sheet.pages = vec![texture_atlas.clone()];
+
+    return texture_atlas;
+}
+
+// Spawns a sprite playing a looping animation over every frame of a sprite sheet with two frames
+fn spawn_walking(
+    app: &mut App,
+    texture_atlas: Option<Handle<TextureAtlas>>,
+) -> (Handle<SpriteSheet>, Handle<AnimationLibrary>, Entity) {
+    let mut sprite_sheet = common::sprite_sheet(&[Some(0.1); 2], Vec::new());
+    add_page(app, &mut sprite_sheet);
+
+    let mut library = AnimationLibrary::from_sprite_sheet(&sprite_sheet);
+    library.add_animation_by_prefix("walk", "frame", 10f32, LoopMode::Loop, Vec2::ZERO);
+
+    if let Some(texture_atlas) = texture_atlas {
+        library.texture_atlas = texture_atlas;
+    }
+
+    library.sprite_sheet = app.world.resource_mut::<Assets<SpriteSheet>>().add(sprite_sheet);
+
+    let sprite_sheet = library.sprite_sheet.clone();
+    let entity = common::spawn(app, library, "walk");
+    let library = app.world.get::<AnimatedSprite>(entity).unwrap().library.clone();
+
+    common::update(app, 0);
+
+    return (sprite_sheet, library, entity);
+}
+
+// Replaces the sprite sheet with one of three frames, like a reloaded data file
+fn grow_sprite_sheet(
+    app: &mut App,
+    handle: &Handle<SpriteSheet>,
+) -> Handle<TextureAtlas> {
+    let mut sprite_sheet = common::sprite_sheet(&[Some(0.1); 3], Vec::new());
+    let texture_atlas = add_page(app, &mut sprite_sheet);
+
+    *app.world.resource_mut::<Assets<SpriteSheet>>().get_mut(handle).unwrap() = sprite_sheet;
+
+    return texture_atlas;
+}
+
+#[test]
+fn reloaded_frames_use_the_reloaded_texture_atlas() {
+    let mut app = common::app();
+
+    let (sprite_sheet, library, entity) = spawn_walking(&mut app, None);
+    let texture_atlas = grow_sprite_sheet(&mut app, &sprite_sheet);
+
+    // The Modified event of the sprite sheet is sent at the end of the first update
+    common::update(&mut app, 0);
+    common::update(&mut app, 0);
+
+    let library = app.world.resource::<Assets<AnimationLibrary>>().get(&library).unwrap();
+
+    assert_eq!(library.texture_atlas, texture_atlas);
+    assert_eq!(library.animation("walk").unwrap().indices, vec![0, 1, 2]);
+
+    // The new frame is shown from the texture atlas containing it
+    common::update(&mut app, 100);
+    common::update(&mut app, 100);
+
+    assert_eq!(common::shown_frame(&app, entity), 2);
+    assert_eq!(*app.world.get::<Handle<TextureAtlas>>(entity).unwrap(), texture_atlas);
+
+    let texture_atlases = app.world.resource::<Assets<TextureAtlas>>();
+    assert_eq!(texture_atlases.get(&texture_atlas).unwrap().textures[2], Rect::new(4.0, 0.0, 6.0, 2.0));
+}
+
+#[test]
+fn assigned_texture_atlases_are_kept() {
+    let mut app = common::app();
+
+    let assigned = app.world.resource_mut::<Assets<TextureAtlas>>().add(TextureAtlas::new_empty(Handle::default(), Vec2::splat(8f32)));
+
+    let (sprite_sheet, library, _) = spawn_walking(&mut app, Some(assigned.clone()));
+    grow_sprite_sheet(&mut app, &sprite_sheet);
+
+    // The Modified event of the sprite sheet is sent at the end of the first update
+    common::update(&mut app, 0);
+    common::update(&mut app, 0);
+
+    let library = app.world.resource::<Assets<AnimationLibrary>>().get(&library).unwrap();
+
+    assert_eq!(library.texture_atlas, assigned);
+    assert_eq!(library.animation("walk").unwrap().indices, vec![0, 1, 2]);
+}
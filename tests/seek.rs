@@ -0,0 +1,128 @@
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use bevy::prelude::*;
+
+use bevy_ss_anim::*;
+
+// Spawns a sprite playing an animation over frames of the durations, starting at the first
+// frame without having played any of it
+fn spawn_walking(
+    app: &mut App,
+    durations: &[f32],
+    loop_mode: LoopMode,
+) -> Entity {
+    let durations: Vec<Option<f32>> = durations.iter().copied().map(Some).collect();
+
+    let mut library = common::library(&durations);
+    library.add_animation_by_range("walk", 0, durations.len() - 1, 4f32, loop_mode, Vec2::ZERO);
+
+    let entity = common::spawn(app, library, "walk");
+    common::update(app, 0);
+
+    return entity;
+}
+
+#[test]
+fn set_frame_starts_the_frame_from_its_beginning() {
+    let mut app = common::app();
+    let entity = spawn_walking(&mut app, &[0.25; 4], LoopMode::Loop);
+
+    common::update(&mut app, 125);
+    common::animated_sprite_mut(&mut app, entity).set_frame(2);
+
+    assert_eq!(common::play(&mut app, entity, 3, 125), vec![2, 2, 3]);
+    assert_eq!(common::animated_sprite(&app, entity).current_frame(), 3);
+}
+
+#[test]
+fn set_frame_is_clamped_to_the_last_frame() {
+    let mut app = common::app();
+    let entity = spawn_walking(&mut app, &[0.25; 4], LoopMode::Loop);
+
+    common::animated_sprite_mut(&mut app, entity).set_frame(10);
+    common::update(&mut app, 0);
+
+    assert_eq!(common::shown_frame(&app, entity), 3);
+
+    // Only the frame that was jumped to is reported
+    let frames: Vec<usize> = common::events::<FrameChanged>(&app).iter().map(|event| event.frame).collect();
+    assert_eq!(frames, vec![3]);
+}
+
+#[test]
+fn set_time_keeps_the_time_within_the_frame() {
+    let mut app = common::app();
+    let entity = spawn_walking(&mut app, &[0.25; 4], LoopMode::Loop);
+
+    common::animated_sprite_mut(&mut app, entity).set_time(0.625);
+
+    assert_eq!(common::play(&mut app, entity, 3, 125), vec![2, 3, 3]);
+}
+
+#[test]
+fn set_normalized_time_uses_the_frame_durations() {
+    let mut app = common::app();
+    let entity = spawn_walking(&mut app, &[0.25, 0.75], LoopMode::Loop);
+
+    // Half of the second is in the middle of the second frame
+    common::animated_sprite_mut(&mut app, entity).set_normalized_time(0.5);
+
+    assert_eq!(common::play(&mut app, entity, 3, 250), vec![1, 1, 0]);
+
+    // Times after the end are clamped to the end of the last frame
+    common::animated_sprite_mut(&mut app, entity).set_normalized_time(2f32);
+    common::update(&mut app, 0);
+
+    assert_eq!(common::shown_frame(&app, entity), 1);
+    assert_eq!(common::play(&mut app, entity, 1, 125), vec![0]);
+}
+
+#[test]
+fn play_animation_at_starts_in_the_middle() {
+    let mut app = common::app();
+    let entity = spawn_walking(&mut app, &[0.25; 4], LoopMode::Loop);
+
+    common::animated_sprite_mut(&mut app, entity).play_animation_at("walk", 0.375);
+    common::update(&mut app, 0);
+
+    assert_eq!(common::shown_frame(&app, entity), 1);
+    assert_eq!(common::events::<AnimationStarted>(&app).len(), 1);
+
+    // The first frame isn't reported, it was never shown
+    let frames: Vec<usize> = common::events::<FrameChanged>(&app).iter().map(|event| event.frame).collect();
+    assert_eq!(frames, vec![1]);
+
+    assert_eq!(common::play(&mut app, entity, 1, 125), vec![2]);
+}
+
+#[test]
+fn seeking_restarts_finished_animations() {
+    let mut app = common::app();
+    let entity = spawn_walking(&mut app, &[0.25; 2], LoopMode::Once);
+
+    common::update(&mut app, 1000);
+    assert!(common::animated_sprite(&app, entity).animation_is_finished);
+
+    common::animated_sprite_mut(&mut app, entity).set_frame(0);
+
+    assert_eq!(common::play(&mut app, entity, 2, 250), vec![0, 1]);
+    assert!(!common::animated_sprite(&app, entity).animation_is_finished);
+
+    common::update(&mut app, 250);
+    assert!(common::animated_sprite(&app, entity).animation_is_finished);
+}
+
+#[test]
+fn seeking_backwards_animations_enters_the_frame_at_its_end() {
+    let mut app = common::app();
+    let entity = spawn_walking(&mut app, &[0.25; 4], LoopMode::Loop);
+
+    common::animated_sprite_mut(&mut app, entity).speed = -1f32;
+
+    // 125 milliseconds into the third frame are 125 milliseconds before its start when played backwards
+    common::animated_sprite_mut(&mut app, entity).set_time(0.625);
+
+    assert_eq!(common::play(&mut app, entity, 3, 125), vec![2, 1, 1]);
+}
@@ -0,0 +1,204 @@
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use bevy::prelude::*;
+
+use bevy_ss_anim::*;
+
+// Spawns a sprite whose state machine starts in the state "idle", playing a looping animation
+// over two frames of 100 milliseconds, every other state plays a single frame
+fn spawn_state_machine(
+    app: &mut App,
+    graph: AnimationGraph,
+) -> Entity {
+    let mut library = AnimationLibrary::from_sprite_sheet(&common::sprite_sheet(&[Some(0.1); 4], Vec::new()));
+    library.add_animation_by_range("idle", 0, 1, 10f32, LoopMode::Loop, Vec2::ZERO);
+    library.add_animation_by_range("walk", 2, 2, 10f32, LoopMode::Loop, Vec2::ZERO);
+    library.add_animation_by_range("jump", 3, 3, 10f32, LoopMode::Once, Vec2::ZERO);
+
+    let graph = app.world.resource_mut::<Assets<AnimationGraph>>().add(graph);
+
+    let entity = common::spawn(app, library, "idle");
+    app.world.entity_mut(entity).insert(AnimationStateMachine::new(graph));
+
+    common::update(app, 0);
+
+    return entity;
+}
+
+// Returns the current state of the state machine
+fn current_state(
+    app: &App,
+    entity: Entity,
+) -> Option<String> {
+    return app.world.get::<AnimationStateMachine>(entity).unwrap().current_state().map(str::to_string);
+}
+
+// Returns the state machine to set its parameters
+fn state_machine_mut(
+    app: &mut App,
+    entity: Entity,
+) -> Mut<'_, AnimationStateMachine> {
+    return app.world.get_mut::<AnimationStateMachine>(entity).unwrap();
+}
+
+// Graph walking while the speed is above 0.5 and jumping from any state when triggered
+fn movement_graph() -> AnimationGraph {
+    let mut graph = AnimationGraph::new("idle");
+    graph.add_state("idle", "idle")
+        .add_state("walk", "walk")
+        .add_state("jump", "jump")
+        .add_transition("idle", "walk", vec![AnimationCondition::Greater("speed".to_string(), 0.5)])
+        .add_transition("walk", "idle", vec![AnimationCondition::Less("speed".to_string(), 0.5)])
+        .add_transition("*", "jump", vec![AnimationCondition::Trigger("jump".to_string())])
+        .add_transition("jump", "idle", vec![AnimationCondition::Finished]);
+
+    return graph;
+}
+
+#[test]
+fn exit_time_counts_the_passes_of_looping_animations() {
+    let mut app = common::app();
+
+    let mut graph = AnimationGraph::new("idle");
+    graph.add_state("idle", "idle")
+        .add_state("walk", "walk")
+        .add_transition("idle", "walk", vec![AnimationCondition::ExitTime(1.5)]);
+
+    let entity = spawn_state_machine(&mut app, graph);
+
+    // The first pass ends after 200 milliseconds, the middle of the second one after 300
+    for _ in 0..3 {
+        common::update(&mut app, 100);
+        assert_eq!(current_state(&app, entity).as_deref(), Some("idle"));
+    }
+
+    common::update(&mut app, 100);
+
+    assert_eq!(current_state(&app, entity).as_deref(), Some("walk"));
+    assert_eq!(common::shown_frame(&app, entity), 2);
+}
+
+#[test]
+fn exit_time_of_one_is_the_end_of_a_looping_pass() {
+    let mut app = common::app();
+
+    let mut graph = AnimationGraph::new("idle");
+    graph.add_state("idle", "idle")
+        .add_state("walk", "walk")
+        .add_transition("idle", "walk", vec![AnimationCondition::ExitTime(1.0)]);
+
+    let entity = spawn_state_machine(&mut app, graph);
+
+    common::update(&mut app, 100);
+    common::update(&mut app, 100);
+
+    assert_eq!(current_state(&app, entity).as_deref(), Some("idle"));
+
+    common::update(&mut app, 100);
+
+    assert_eq!(current_state(&app, entity).as_deref(), Some("walk"));
+}
+
+#[test]
+fn float_parameters_switch_states_in_the_same_update() {
+    let mut app = common::app();
+    let entity = spawn_state_machine(&mut app, movement_graph());
+
+    assert_eq!(current_state(&app, entity).as_deref(), Some("idle"));
+
+    state_machine_mut(&mut app, entity).set_float("speed", 1f32);
+    common::update(&mut app, 0);
+
+    assert_eq!(current_state(&app, entity).as_deref(), Some("walk"));
+    assert_eq!(common::shown_frame(&app, entity), 2);
+
+    state_machine_mut(&mut app, entity).set_float("speed", 0f32);
+    common::update(&mut app, 0);
+
+    assert_eq!(current_state(&app, entity).as_deref(), Some("idle"));
+    assert_eq!(common::shown_frame(&app, entity), 0);
+}
+
+#[test]
+fn bool_parameters_are_false_until_set() {
+    let mut app = common::app();
+
+    let mut graph = AnimationGraph::new("idle");
+    graph.add_state("idle", "idle")
+        .add_state("walk", "walk")
+        .add_transition("idle", "walk", vec![AnimationCondition::Bool("walking".to_string(), true)])
+        .add_transition("walk", "idle", vec![AnimationCondition::Bool("walking".to_string(), false)]);
+
+    let entity = spawn_state_machine(&mut app, graph);
+
+    common::update(&mut app, 0);
+    assert_eq!(current_state(&app, entity).as_deref(), Some("idle"));
+
+    state_machine_mut(&mut app, entity).set_bool("walking", true);
+    common::update(&mut app, 0);
+    assert_eq!(current_state(&app, entity).as_deref(), Some("walk"));
+
+    state_machine_mut(&mut app, entity).set_bool("walking", false);
+    common::update(&mut app, 0);
+    assert_eq!(current_state(&app, entity).as_deref(), Some("idle"));
+}
+
+#[test]
+fn triggers_are_used_up_by_their_transition() {
+    let mut app = common::app();
+    let entity = spawn_state_machine(&mut app, movement_graph());
+
+    state_machine_mut(&mut app, entity).set_trigger("jump");
+    common::update(&mut app, 0);
+
+    assert_eq!(current_state(&app, entity).as_deref(), Some("jump"));
+    assert_eq!(common::shown_frame(&app, entity), 3);
+
+    // The jump finishes after its only frame, the state machine returns to idle in the next update
+    common::update(&mut app, 100);
+    assert_eq!(current_state(&app, entity).as_deref(), Some("jump"));
+
+    common::update(&mut app, 0);
+    assert_eq!(current_state(&app, entity).as_deref(), Some("idle"));
+
+    // Without the trigger the state machine doesn't jump again
+    common::update(&mut app, 0);
+    assert_eq!(current_state(&app, entity).as_deref(), Some("idle"));
+}
+
+#[test]
+fn reset_triggers_are_not_used() {
+    let mut app = common::app();
+    let entity = spawn_state_machine(&mut app, movement_graph());
+
+    let mut state_machine = state_machine_mut(&mut app, entity);
+    state_machine.set_trigger("jump");
+    state_machine.reset_trigger("jump");
+
+    common::update(&mut app, 0);
+
+    assert_eq!(current_state(&app, entity).as_deref(), Some("idle"));
+}
+
+#[test]
+fn any_state_transitions_dont_restart_their_target() {
+    let mut app = common::app();
+    let entity = spawn_state_machine(&mut app, movement_graph());
+
+    state_machine_mut(&mut app, entity).set_trigger("jump");
+    common::update(&mut app, 0);
+
+    assert_eq!(common::events::<AnimationStarted>(&app).len(), 1);
+
+    // Triggering the jump while jumping keeps the jump playing
+    state_machine_mut(&mut app, entity).set_trigger("jump");
+    common::update(&mut app, 50);
+
+    assert_eq!(current_state(&app, entity).as_deref(), Some("jump"));
+    assert!(common::events::<AnimationStarted>(&app).is_empty());
+
+    common::update(&mut app, 50);
+    assert!(common::animated_sprite(&app, entity).animation_is_finished);
+}